@@ -0,0 +1,357 @@
+use crate::Color;
+
+// WCAG 2 contrast thresholds
+pub const AA_NORMAL: f64 = 4.5;
+pub const AA_LARGE: f64 = 3.0;
+pub const AAA_NORMAL: f64 = 7.0;
+
+#[allow(dead_code)]
+// A WCAG 2 conformance target for text contrast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WcagLevel {
+    // AA for large (18pt, or 14pt bold) text
+    AaLarge,
+    Aa,
+    Aaa,
+}
+
+impl WcagLevel {
+    pub fn min_ratio(&self) -> f64 {
+        match self {
+            WcagLevel::AaLarge => AA_LARGE,
+            WcagLevel::Aa => AA_NORMAL,
+            WcagLevel::Aaa => AAA_NORMAL,
+        }
+    }
+}
+
+// Contrast between one pair of palette colors
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastEntry<'a> {
+    pub a_name: &'a str,
+    pub b_name: &'a str,
+    pub ratio: f64,
+    // Passes for normal-size text
+    pub passes_aa: bool,
+    pub passes_aaa: bool,
+}
+
+// Forms of color vision deficiency we can simulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Deficiency::Protanopia => "protanopia",
+            Deficiency::Deuteranopia => "deuteranopia",
+            Deficiency::Tritanopia => "tritanopia",
+        }
+    }
+
+    // Machado, Oliveira & Fernandes (2009) at full severity, linear RGB
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_82, 0.042_94, 0.968_881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_9],
+            ],
+        }
+    }
+}
+
+impl Color {
+    // WCAG 2 relative luminance
+    pub fn relative_luminance(&self) -> f64 {
+        let [r, g, b] = self.to_linear_rgb().map(f64::from);
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    // WCAG 2 contrast ratio, from 1 (identical) to 21 (black on white)
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        let (light, dark) = if a > b { (a, b) } else { (b, a) };
+        (light + 0.05) / (dark + 0.05)
+    }
+
+    // How the color appears to someone with the given deficiency
+    pub fn simulate_cvd(&self, deficiency: Deficiency) -> Color {
+        let m = deficiency.matrix();
+        let [r, g, b] = self.to_linear_rgb();
+        Color::from_linear_rgb([
+            m[0][0] * r + m[0][1] * g + m[0][2] * b,
+            m[1][0] * r + m[1][1] * g + m[1][2] * b,
+            m[2][0] * r + m[2][1] * g + m[2][2] * b,
+        ])
+    }
+
+    #[allow(dead_code)]
+    // Night (rod-only) vision: a gray whose brightness follows the scotopic
+    // luminosity curve, peaking at 507 nm rather than 555 nm, so blues look
+    // lighter and reds darker than by day (the Purkinje effect). Uses the
+    // XYZ approximation of Larson et al. (1997), scaled so white stays white.
+    pub fn simulate_scotopic(&self) -> Color {
+        let scotopic = |[x, y, z]: [f32; 3]| {
+            if x <= 0.0 {
+                0.0
+            } else {
+                y * (1.33 * (1.0 + (y + z) / x) - 1.68)
+            }
+        };
+        let white = scotopic(Color::from_rgb(255, 255, 255).to_xyz());
+        let v = (scotopic(self.to_xyz()) / white).clamp(0.0, 1.0);
+        Color::from_linear_rgb([v, v, v])
+    }
+}
+
+// Every fully saturated HSL color on a `hue_step` degree hue grid (in 5%
+// lightness steps) that reaches WCAG AA contrast on `bg`, highest contrast
+// first. A non-positive step yields nothing.
+pub fn accessible_text_colors_for_background(bg: &Color, hue_step: f32) -> Vec<Color> {
+    if hue_step.is_nan() || hue_step <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<(Color, f64)> = Vec::new();
+    let mut hue = 0.0;
+    while hue < 360.0 {
+        for step in 0..=20 {
+            let color = Color::from_hsl(hue, 1.0, step as f32 / 20.0);
+            let ratio = color.contrast_ratio(bg);
+            if ratio >= AA_NORMAL && !candidates.iter().any(|(c, _)| *c == color) {
+                candidates.push((color, ratio));
+            }
+        }
+        hue += hue_step;
+    }
+
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    candidates.into_iter().map(|(color, _)| color).collect()
+}
+
+// Contrast of every unordered pair of palette colors, highest ratio
+// first. Pairs with equal ratios stay in palette order.
+pub fn palette_contrast_report<'a>(palette: &[(&'a str, Color)]) -> Vec<ContrastEntry<'a>> {
+    let mut entries = Vec::with_capacity(palette.len() * palette.len().saturating_sub(1) / 2);
+    for (i, (a_name, a)) in palette.iter().enumerate() {
+        for (b_name, b) in &palette[i + 1..] {
+            let ratio = a.contrast_ratio(b);
+            entries.push(ContrastEntry {
+                a_name,
+                b_name,
+                ratio,
+                passes_aa: ratio >= AA_NORMAL,
+                passes_aaa: ratio >= AAA_NORMAL,
+            });
+        }
+    }
+    entries.sort_by(|x, y| y.ratio.total_cmp(&x.ratio));
+    entries
+}
+
+// SplitMix64: a tiny, well-mixed generator, plenty for picking colors
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// A text color of the given HSL hue and saturation for `background`: the
+// lightness closest to 0.5 that meets `level`, searching towards white on
+// dark backgrounds and towards black on light ones. If nothing does, the
+// extreme itself (plain white or black) is returned.
+pub fn text_in_hue_for_background(
+    hue: f32,
+    saturation: f32,
+    background: &Color,
+    level: WcagLevel,
+) -> Color {
+    let white = Color::from_rgb(255, 255, 255);
+    let black = Color::from_rgb(0, 0, 0);
+    let dark = white.contrast_ratio(background) > black.contrast_ratio(background);
+    (0..=50)
+        .map(|step| {
+            let offset = step as f32 / 100.0;
+            Color::from_hsl(
+                hue,
+                saturation,
+                if dark { 0.5 + offset } else { 0.5 - offset },
+            )
+        })
+        .find(|text| text.contrast_ratio(background) >= level.min_ratio())
+        .unwrap_or(if dark { white } else { black })
+}
+
+#[allow(dead_code)]
+// A (foreground, background) pair for prototyping, fully determined by
+// `seed`. Odd seeds give a dark background, even seeds a light one. The
+// background is a muted tint; the foreground has its own hue, picked with
+// `text_in_hue_for_background`.
+pub fn random_accessible_pair(seed: u64, level: WcagLevel) -> (Color, Color) {
+    let mut state = seed;
+    let mut unit = || (splitmix64(&mut state) >> 40) as f32 / (1u64 << 24) as f32;
+    let dark = seed % 2 == 1;
+
+    let bg_l = if dark {
+        0.05 + unit() * 0.15
+    } else {
+        0.8 + unit() * 0.15
+    };
+    let background = Color::from_hsl(unit() * 360.0, 0.05 + unit() * 0.15, bg_l);
+
+    let (fg_h, fg_s) = (unit() * 360.0, 0.3 + unit() * 0.4);
+    let foreground = text_in_hue_for_background(fg_h, fg_s, &background, level);
+    (foreground, background)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accessible_text_on_white_is_dark() {
+        let white = Color::from_rgb(255, 255, 255);
+        // 4.5:1 against white means (1 + 0.05) / (L + 0.05) >= 4.5
+        let max_luminance = 1.05 / AA_NORMAL - 0.05;
+        for step in [15.0, 7.5, 1.0] {
+            let colors = accessible_text_colors_for_background(&white, step);
+            assert!(!colors.is_empty());
+            for color in &colors {
+                assert!(
+                    color.relative_luminance() <= max_luminance,
+                    "{color:?} has luminance {}",
+                    color.relative_luminance()
+                );
+                assert!(color.contrast_ratio(&white) >= AA_NORMAL);
+            }
+            assert!(colors
+                .windows(2)
+                .all(|w| w[0].contrast_ratio(&white) >= w[1].contrast_ratio(&white)));
+        }
+        // Just inside the limit, at luminance 0.1806 and 4.55:1
+        assert!(accessible_text_colors_for_background(&white, 15.0)
+            .contains(&Color::from_rgb(230, 0, 115)));
+    }
+
+    #[test]
+    fn accessible_text_needs_a_positive_step() {
+        let white = Color::from_rgb(255, 255, 255);
+        for step in [0.0, -15.0, f32::NAN] {
+            assert!(accessible_text_colors_for_background(&white, step).is_empty());
+        }
+    }
+
+    #[test]
+    fn contrast_report_covers_every_unique_pair() {
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        let colors = crate::palette::web_safe_palette();
+        for n in 0..=names.len() {
+            let palette: Vec<(&str, Color)> = names[..n]
+                .iter()
+                .zip(colors.iter().step_by(31))
+                .map(|(&name, color)| (name, color.clone()))
+                .collect();
+            let report = palette_contrast_report(&palette);
+            assert_eq!(report.len(), n * n.saturating_sub(1) / 2);
+            assert!(report.iter().all(|entry| entry.a_name != entry.b_name));
+            assert!(report.windows(2).all(|w| w[0].ratio >= w[1].ratio));
+        }
+    }
+
+    #[test]
+    fn contrast_report_flags_aa_and_aaa() {
+        let palette = [
+            ("black", Color::from_rgb(0, 0, 0)),
+            ("white", Color::from_rgb(255, 255, 255)),
+            ("gray", Color::from_rgb(118, 118, 118)),
+        ];
+        let report = palette_contrast_report(&palette);
+        let flags: Vec<(&str, &str, bool, bool)> = report
+            .iter()
+            .map(|e| (e.a_name, e.b_name, e.passes_aa, e.passes_aaa))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                ("black", "white", true, true),
+                ("black", "gray", true, false),
+                ("white", "gray", true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn random_pairs_always_meet_the_level() {
+        for level in [WcagLevel::AaLarge, WcagLevel::Aa, WcagLevel::Aaa] {
+            for seed in (0..2000).chain([u64::MAX - 1, u64::MAX]) {
+                let (foreground, background) = random_accessible_pair(seed, level);
+                let ratio = foreground.contrast_ratio(&background);
+                assert!(
+                    ratio >= level.min_ratio(),
+                    "seed {seed}: {foreground:?} on {background:?} is {ratio:.2}:1"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_pairs_are_deterministic_and_alternate_modes() {
+        for seed in 0..200 {
+            let pair = random_accessible_pair(seed, WcagLevel::Aa);
+            assert_eq!(pair, random_accessible_pair(seed, WcagLevel::Aa));
+            let (_, _, l) = pair.1.to_hsl();
+            if seed % 2 == 1 {
+                assert!(l <= 0.21, "seed {seed}: background lightness {l}");
+            } else {
+                assert!(l >= 0.79, "seed {seed}: background lightness {l}");
+            }
+        }
+    }
+
+    #[test]
+    fn scotopic_vision_darkens_red_and_lightens_blue() {
+        let red = Color::from_rgb(255, 0, 0);
+        let blue = Color::from_rgb(0, 0, 255);
+        let night_red = red.simulate_scotopic();
+        let night_blue = blue.simulate_scotopic();
+        for night in [&night_red, &night_blue] {
+            assert!(night.r == night.g && night.g == night.b, "{night:?}");
+        }
+        // Relative to daytime luminance, red loses brightness and blue gains it
+        assert!(night_red.relative_luminance() < red.relative_luminance() / 2.0);
+        assert!(night_blue.relative_luminance() > blue.relative_luminance());
+        assert!(night_blue.relative_luminance() > night_red.relative_luminance());
+        assert_eq!(
+            Color::from_rgb(255, 255, 255).simulate_scotopic(),
+            Color::from_rgb(255, 255, 255)
+        );
+        assert_eq!(
+            Color::from_rgb(0, 0, 0).simulate_scotopic(),
+            Color::from_rgb(0, 0, 0)
+        );
+    }
+}
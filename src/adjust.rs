@@ -0,0 +1,105 @@
+use crate::Color;
+
+// A set of HSL adjustments applied together by `Color::adjust`. Amounts
+// are relative: `lighten(0.1)` moves lightness 10% of the way to white,
+// `darken(0.1)` 10% of the way to black, and likewise for saturation.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Adjustment {
+    pub lighten: Option<f32>,
+    pub darken: Option<f32>,
+    pub saturate: Option<f32>,
+    pub desaturate: Option<f32>,
+    // Degrees, positive or negative
+    pub rotate_hue: Option<f32>,
+}
+
+#[allow(dead_code)]
+impl Adjustment {
+    // No adjustments
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lighten(mut self, amount: f32) -> Self {
+        self.lighten = Some(amount);
+        self
+    }
+
+    pub fn darken(mut self, amount: f32) -> Self {
+        self.darken = Some(amount);
+        self
+    }
+
+    pub fn saturate(mut self, amount: f32) -> Self {
+        self.saturate = Some(amount);
+        self
+    }
+
+    pub fn desaturate(mut self, amount: f32) -> Self {
+        self.desaturate = Some(amount);
+        self
+    }
+
+    pub fn rotate_hue(mut self, degrees: f32) -> Self {
+        self.rotate_hue = Some(degrees);
+        self
+    }
+}
+
+impl Color {
+    #[allow(dead_code)]
+    // Apply the adjustments in HSL, in field order: lighten, darken,
+    // saturate, desaturate, then rotate the hue. Amounts are clamped to
+    // [0, 1]. Because each step is relative to the current value,
+    // lightening then darkening by the same amount doesn't fully cancel.
+    pub fn adjust(&self, adj: &Adjustment) -> Color {
+        let (mut h, mut s, mut l) = self.to_hsl();
+        let amount = |a: Option<f32>| a.unwrap_or(0.0).clamp(0.0, 1.0);
+        l += (1.0 - l) * amount(adj.lighten);
+        l -= l * amount(adj.darken);
+        s += (1.0 - s) * amount(adj.saturate);
+        s -= s * amount(adj.desaturate);
+        if let Some(degrees) = adj.rotate_hue {
+            h = (h + degrees).rem_euclid(360.0);
+        }
+        Color::from_hsl(h, s, l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lightness(color: &Color) -> f32 {
+        color.to_hsl().2
+    }
+
+    #[test]
+    fn lighten_then_darken_only_partly_cancels() {
+        let base = Color::from_hsl(200.0, 0.6, 0.2);
+        let lightened = base.adjust(&Adjustment::new().lighten(0.1));
+        let both = base.adjust(&Adjustment::new().lighten(0.1).darken(0.1));
+        // 0.2 + 0.08 = 0.28, then 0.28 - 0.028 = 0.252
+        assert!((lightness(&both) - 0.252).abs() < 0.003, "{both:?}");
+        assert!(lightness(&base) < lightness(&both));
+        assert!(lightness(&both) < lightness(&lightened));
+    }
+
+    #[test]
+    fn adjustments_apply_together() {
+        let base = Color::from_hsl(0.0, 0.5, 0.5);
+        assert_eq!(base.adjust(&Adjustment::new()), base);
+        let adjusted = base.adjust(&Adjustment::new().saturate(1.0).rotate_hue(-120.0));
+        assert_eq!(adjusted, Color::from_rgb(0, 0, 255));
+        // Amounts are clamped to [0, 1]
+        assert_eq!(
+            base.adjust(&Adjustment::new().lighten(5.0)),
+            Color::from_rgb(255, 255, 255)
+        );
+        assert_eq!(
+            base.adjust(&Adjustment::new().desaturate(1.0)),
+            Color::from_rgb(128, 128, 128)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::mix::InterpolationSpace;
+use crate::Color;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Terminals can't usefully redraw faster than this
+pub const MAX_FPS: u32 = 60;
+
+// Every frame is generated up front, so keep animations to a sane length
+pub const MAX_DURATION: Duration = Duration::from_secs(60 * 60);
+
+// What changes over the course of an animation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    // Rotate the hue by up to this many degrees
+    Rotate(f32),
+    // Raise HSL lightness by up to this amount (0-1)
+    Lighten(f32),
+    // Lower HSL lightness by up to this amount (0-1)
+    Darken(f32),
+    // Blend towards another color
+    Gradient(Color, InterpolationSpace),
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    // Accepts rotate:DEGREES, lighten:AMOUNT, darken:AMOUNT or gradient:COLOR
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected OPERATION:VALUE, got `{s}`"))?;
+        let number = || {
+            arg.trim()
+                .parse::<f32>()
+                .map_err(|_| format!("invalid value `{arg}` for {name}"))
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rotate" => Ok(Operation::Rotate(number()?)),
+            "lighten" => Ok(Operation::Lighten(number()?)),
+            "darken" => Ok(Operation::Darken(number()?)),
+            "gradient" => arg
+                .parse()
+                .map(|target| Operation::Gradient(target, InterpolationSpace::Srgb))
+                .map_err(|e| format!("invalid gradient target `{arg}`: {e}")),
+            _ => Err(format!(
+                "unknown operation `{name}` (expected rotate, lighten, darken or gradient)"
+            )),
+        }
+    }
+}
+
+// Parse durations such as `5s`, `1.5s`, `250ms` or a bare number of seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (number, scale) = if let Some(ms) = s.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        (secs, 1.0)
+    } else {
+        (s, 1.0)
+    };
+    let invalid = || format!("invalid duration `{s}`");
+    let value: f64 = number.trim().parse().map_err(|_| invalid())?;
+    // Rejects negative, NaN and out-of-range values alike
+    let duration = Duration::try_from_secs_f64(value * scale).map_err(|_| invalid())?;
+    if duration > MAX_DURATION {
+        return Err(format!(
+            "duration `{s}` is longer than the {}s limit",
+            MAX_DURATION.as_secs()
+        ));
+    }
+    Ok(duration)
+}
+
+// The color at progress `t` in [0, 1] through the operation
+pub fn frame_color(base: &Color, operation: &Operation, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    match operation {
+        Operation::Rotate(degrees) => {
+            let (h, s, v) = base.to_hsv();
+            Color::from_hsv((h + degrees * t).rem_euclid(360.0), s, v)
+        }
+        Operation::Lighten(amount) | Operation::Darken(amount) => {
+            let sign = if matches!(operation, Operation::Lighten(_)) {
+                1.0
+            } else {
+                -1.0
+            };
+            let (h, s, l) = base.to_hsl();
+            Color::from_hsl(h, s, (l + sign * amount * t).clamp(0.0, 1.0))
+        }
+        Operation::Gradient(target, space) => Color::interpolate(base, target, t, *space),
+    }
+}
+
+// Every frame of the animation, first and last included
+pub fn frames(base: &Color, operation: &Operation, duration: Duration, fps: u32) -> Vec<Color> {
+    let steps = (duration.as_secs_f64() * f64::from(fps.clamp(1, MAX_FPS)))
+        .round()
+        .max(1.0) as usize;
+    (0..=steps)
+        .map(|i| frame_color(base, operation, i as f32 / steps as f32))
+        .collect()
+}
+
+#[cfg(unix)]
+mod interrupt {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const SIGINT: i32 = 2;
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    }
+
+    extern "C" fn on_sigint(_: i32) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+
+    // Catch Ctrl-C so the terminal can be restored before exiting
+    pub fn install() {
+        // SAFETY: the handler only stores to an atomic, which is signal safe
+        unsafe {
+            signal(SIGINT, on_sigint);
+        }
+    }
+
+    pub fn interrupted() -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(not(unix))]
+mod interrupt {
+    pub fn install() {}
+
+    pub fn interrupted() -> bool {
+        false
+    }
+}
+
+fn swatch(color: &Color) -> String {
+    format!(
+        "\x1b[48;2;{};{};{}m        \x1b[0m {} {}",
+        color.r,
+        color.g,
+        color.b,
+        color.to_hex(),
+        color
+    )
+}
+
+// Play the frames, redrawing one line in place on a terminal. Anywhere
+// else each frame is printed on its own line without escape codes.
+pub fn play(frames: &[Color], frame_time: Duration, tty: bool) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    if !tty {
+        for (i, color) in frames.iter().enumerate() {
+            writeln!(out, "{i}\t{}\t{color}", color.to_hex())?;
+        }
+        return Ok(());
+    }
+
+    interrupt::install();
+    write!(out, "\x1b[?25l")?;
+    let start = Instant::now();
+    for (i, color) in frames.iter().enumerate() {
+        if interrupt::interrupted() {
+            break;
+        }
+        write!(out, "\r{}\x1b[K", swatch(color))?;
+        out.flush()?;
+
+        let Some(due) = u32::try_from(i + 1)
+            .ok()
+            .and_then(|n| frame_time.checked_mul(n))
+        else {
+            break;
+        };
+        while start.elapsed() < due && !interrupt::interrupted() {
+            thread::sleep(
+                due.saturating_sub(start.elapsed())
+                    .min(Duration::from_millis(20)),
+            );
+        }
+    }
+    writeln!(out, "\x1b[0m\x1b[?25h")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_accepts_units() {
+        assert_eq!(parse_duration("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration(" 1.5 "), Ok(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_out_of_range_values() {
+        for input in ["1e30s", "-1s", "nans", "inf", "fast"] {
+            assert_eq!(
+                parse_duration(input),
+                Err(format!("invalid duration `{input}`"))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_duration_rejects_durations_over_the_limit() {
+        assert_eq!(parse_duration("3600s"), Ok(MAX_DURATION));
+        assert_eq!(
+            parse_duration("100000000000s"),
+            Err("duration `100000000000s` is longer than the 3600s limit".to_string())
+        );
+    }
+
+    #[test]
+    fn frames_run_from_base_to_target() {
+        let base = Color { r: 255, g: 0, b: 0 };
+        let target = Color { r: 0, g: 0, b: 255 };
+        let operation = Operation::Gradient(target.clone(), InterpolationSpace::Srgb);
+        let frames = frames(&base, &operation, Duration::from_secs(2), 10);
+        assert_eq!(frames.len(), 2 * 10 + 1);
+        assert_eq!(frames.first(), Some(&base));
+        assert_eq!(frames.last(), Some(&target));
+    }
+
+    #[test]
+    fn frame_count_caps_fps_and_keeps_both_ends() {
+        let base = Color { r: 255, g: 0, b: 0 };
+        let operation = Operation::Rotate(120.0);
+        let capped = frames(&base, &operation, Duration::from_secs(1), 1000);
+        assert_eq!(capped.len(), MAX_FPS as usize + 1);
+        // Even an instant animation shows the start and the end
+        let instant = frames(&base, &operation, Duration::ZERO, 30);
+        assert_eq!(instant.len(), 2);
+        assert_eq!(instant[1], Color { r: 0, g: 255, b: 0 });
+    }
+
+    #[test]
+    fn frame_color_clamps_progress_and_applies_each_operation() {
+        let base = Color::from_hsl(0.0, 1.0, 0.5);
+        assert_eq!(frame_color(&base, &Operation::Rotate(240.0), 0.0), base);
+        assert_eq!(
+            frame_color(&base, &Operation::Rotate(240.0), 2.0),
+            Color { r: 0, g: 0, b: 255 }
+        );
+        assert_eq!(
+            frame_color(&base, &Operation::Lighten(0.5), 1.0),
+            Color {
+                r: 255,
+                g: 255,
+                b: 255
+            }
+        );
+        assert_eq!(
+            frame_color(&base, &Operation::Darken(0.5), 1.0),
+            Color { r: 0, g: 0, b: 0 }
+        );
+    }
+}
@@ -0,0 +1,1014 @@
+use crate::error::ColorError;
+use crate::mix::HueDirection;
+use crate::space::{self, WhitePoint};
+use crate::Color;
+use std::str::FromStr;
+
+// Encoded channels within a couple of 8-bit steps of [0, 1] still count
+// as in gamut, so rounded component values such as
+// `oklch(62.8% 0.258 29.23)` (sRGB red) don't warn
+const GAMUT_EPSILON: f32 = 2.0 / 255.0;
+
+// A color read from CSS, with the parts that don't fit in a `Color`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssColor {
+    pub color: Color,
+    pub alpha: f32,
+    // The value was outside sRGB and has been clipped to it
+    pub clipped: bool,
+}
+
+// A CSS color function split into its parts, e.g. `hsl(120deg 50% 50% / 0.5)`
+pub struct CssFunction<'a> {
+    pub name: String,
+    pub components: Vec<&'a str>,
+    pub alpha: Option<&'a str>,
+}
+
+// Split `name(a b c / alpha)` or the legacy `name(a, b, c, alpha)` form
+pub fn parse_function(s: &str) -> Result<CssFunction<'_>, ColorError> {
+    let s = s.trim();
+    let open = s
+        .find('(')
+        .ok_or_else(|| ColorError::Syntax(format!("`{s}` is not a CSS color function")))?;
+    let body = s[open + 1..]
+        .strip_suffix(')')
+        .ok_or_else(|| ColorError::Syntax(format!("`{s}` is missing its closing parenthesis")))?;
+    let name = s[..open].trim().to_ascii_lowercase();
+
+    let (body, mut alpha) = match body.split_once('/') {
+        Some((components, alpha)) => (components, Some(alpha.trim())),
+        None => (body, None),
+    };
+    let mut components: Vec<&str> = if body.contains(',') {
+        body.split(',').map(str::trim).collect()
+    } else {
+        body.split_whitespace().collect()
+    };
+    if alpha.is_none() && body.contains(',') && components.len() == 4 {
+        alpha = components.pop();
+    }
+    if components.iter().any(|c| c.is_empty()) {
+        return Err(ColorError::Syntax(format!("`{s}` has an empty component")));
+    }
+    Ok(CssFunction {
+        name,
+        components,
+        alpha,
+    })
+}
+
+// Parse a CSS <angle> (`deg`, `grad`, `rad`, `turn`, or a bare number of
+// degrees) and wrap it into [0, 360)
+pub fn parse_css_angle(s: &str) -> Result<f32, ColorError> {
+    let s = s.trim();
+    let lower = s.to_ascii_lowercase();
+    // `grad` has to be tried before `rad`
+    let (number, to_degrees) = if let Some(n) = lower.strip_suffix("deg") {
+        (n, 1.0)
+    } else if let Some(n) = lower.strip_suffix("grad") {
+        (n, 360.0 / 400.0)
+    } else if let Some(n) = lower.strip_suffix("rad") {
+        (n, 180.0 / std::f32::consts::PI)
+    } else if let Some(n) = lower.strip_suffix("turn") {
+        (n, 360.0)
+    } else {
+        (lower.as_str(), 1.0)
+    };
+    let value: f32 = number
+        .trim()
+        .parse()
+        .map_err(|_| ColorError::Syntax(format!("`{s}` is not a CSS angle")))?;
+    if !value.is_finite() {
+        return Err(ColorError::Syntax(format!("`{s}` is not a CSS angle")));
+    }
+    Ok((value * to_degrees).rem_euclid(360.0))
+}
+
+// An alpha value, either a number in [0, 1], a percentage or `none`
+pub fn parse_css_alpha(s: &str) -> Result<f32, ColorError> {
+    if s.trim().eq_ignore_ascii_case("none") {
+        return Ok(0.0);
+    }
+    let (number, scale) = match s.trim().strip_suffix('%') {
+        Some(percent) => (percent, 0.01),
+        None => (s.trim(), 1.0),
+    };
+    let value: f32 = number
+        .trim()
+        .parse()
+        .map_err(|_| ColorError::Syntax(format!("invalid alpha `{s}`")))?;
+    Ok((value * scale).clamp(0.0, 1.0))
+}
+
+// A percentage in `hsl()`, also accepted as a bare number, as a fraction
+fn parse_hsl_percentage(component: &'static str, s: &str) -> Result<f32, ColorError> {
+    let number = s.strip_suffix('%').unwrap_or(s).trim();
+    let value: f32 = number
+        .parse()
+        .map_err(|_| ColorError::Syntax(format!("invalid {component} `{s}`")))?;
+    Ok((value / 100.0).clamp(0.0, 1.0))
+}
+
+// A number, or a percentage where 100% is `percent_scale`. `none` is 0.
+fn parse_css_number(
+    component: &'static str,
+    s: &str,
+    percent_scale: f32,
+) -> Result<f32, ColorError> {
+    if s.eq_ignore_ascii_case("none") {
+        return Ok(0.0);
+    }
+    let (number, scale) = match s.strip_suffix('%') {
+        Some(percent) => (percent, percent_scale / 100.0),
+        None => (s, 1.0),
+    };
+    let value: f32 = number
+        .trim()
+        .parse()
+        .map_err(|_| ColorError::Syntax(format!("invalid {component} `{s}`")))?;
+    if !value.is_finite() {
+        return Err(ColorError::Syntax(format!("invalid {component} `{s}`")));
+    }
+    Ok(value * scale)
+}
+
+fn parse_css_hue(s: &str) -> Result<f32, ColorError> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(0.0)
+    } else {
+        parse_css_angle(s)
+    }
+}
+
+fn polar_to_ab(chroma: f32, hue: f32) -> (f32, f32) {
+    let (sin, cos) = hue.to_radians().sin_cos();
+    (chroma.max(0.0) * cos, chroma.max(0.0) * sin)
+}
+
+fn from_linear(linear: [f32; 3], alpha: f32) -> CssColor {
+    let clipped = linear
+        .iter()
+        .any(|&c| !(-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(&space::linear_to_srgb(c)));
+    CssColor {
+        color: Color::from_linear_rgb(linear),
+        alpha,
+        clipped,
+    }
+}
+
+// The CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, [u8; 3]); 148] = [
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
+
+// A CSS named color such as `rebeccapurple`, ignoring ASCII case
+pub fn named_color(name: &str) -> Option<Color> {
+    let name = name.trim().to_ascii_lowercase();
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+        .ok()
+        .map(|i| {
+            let [r, g, b] = NAMED_COLORS[i].1;
+            Color::from_rgb(r, g, b)
+        })
+}
+
+// Parse a hex code, a named color (`transparent` included), or a CSS color
+// function: rgb(), hsl(), lab(), lch(), oklab(), oklch(), or color() in the
+// srgb, srgb-linear or display-p3 spaces. Components accept numbers,
+// percentages and `none`. Colors outside sRGB are clipped channel by
+// channel and flagged as `clipped`.
+pub fn parse_css_color(s: &str) -> Result<CssColor, ColorError> {
+    if s.trim().eq_ignore_ascii_case("transparent") {
+        return Ok(CssColor {
+            color: Color::from_rgb(0, 0, 0),
+            alpha: 0.0,
+            clipped: false,
+        });
+    }
+    if let Some(color) = named_color(s) {
+        return Ok(CssColor {
+            color,
+            alpha: 1.0,
+            clipped: false,
+        });
+    }
+    if !s.contains('(') {
+        let color =
+            Color::from_hex(s.trim()).map_err(|e| ColorError::Syntax(format!("`{s}`: {e}")))?;
+        return Ok(CssColor {
+            color,
+            alpha: 1.0,
+            clipped: false,
+        });
+    }
+    let function = parse_function(s)?;
+    let name = function.name.as_str();
+    let mut components = function.components.as_slice();
+
+    let space = if name == "color" {
+        let (space, rest) = components
+            .split_first()
+            .ok_or_else(|| ColorError::Syntax("color() needs a color space".to_string()))?;
+        components = rest;
+        Some(space.to_ascii_lowercase())
+    } else {
+        None
+    };
+    if components.len() != 3 {
+        return Err(ColorError::Syntax(format!(
+            "{name}() takes 3 components, got {}",
+            components.len()
+        )));
+    }
+    let alpha = match function.alpha {
+        Some(alpha) => parse_css_alpha(alpha)?,
+        None => 1.0,
+    };
+    let [c0, c1, c2] = [components[0], components[1], components[2]];
+
+    let linear = match (name, space.as_deref()) {
+        ("rgb" | "rgba", _) => {
+            let channel = |component, s| {
+                parse_css_number(component, s, 255.0).map(|c| space::srgb_to_linear(c / 255.0))
+            };
+            [
+                channel("red", c0)?,
+                channel("green", c1)?,
+                channel("blue", c2)?,
+            ]
+        }
+        ("hsl" | "hsla", _) => {
+            let color = Color::from_hsl_css(s)?;
+            return Ok(CssColor {
+                color,
+                alpha,
+                clipped: false,
+            });
+        }
+        ("lab", _) => {
+            let l = parse_css_number("lightness", c0, 100.0)?.clamp(0.0, 100.0);
+            let a = parse_css_number("a", c1, 125.0)?;
+            let b = parse_css_number("b", c2, 125.0)?;
+            space::xyz_to_linear_srgb(space::lab_to_xyz(l, a, b, WhitePoint::D50))
+        }
+        ("lch", _) => {
+            let l = parse_css_number("lightness", c0, 100.0)?.clamp(0.0, 100.0);
+            let (a, b) = polar_to_ab(parse_css_number("chroma", c1, 150.0)?, parse_css_hue(c2)?);
+            space::xyz_to_linear_srgb(space::lab_to_xyz(l, a, b, WhitePoint::D50))
+        }
+        ("oklab", _) => {
+            let l = parse_css_number("lightness", c0, 1.0)?.clamp(0.0, 1.0);
+            let a = parse_css_number("a", c1, 0.4)?;
+            let b = parse_css_number("b", c2, 0.4)?;
+            space::oklab_to_linear_srgb([l, a, b])
+        }
+        ("oklch", _) => {
+            let l = parse_css_number("lightness", c0, 1.0)?.clamp(0.0, 1.0);
+            let (a, b) = polar_to_ab(parse_css_number("chroma", c1, 0.4)?, parse_css_hue(c2)?);
+            space::oklab_to_linear_srgb([l, a, b])
+        }
+        ("color", Some(space)) => {
+            let rgb = [
+                parse_css_number("red", c0, 1.0)?,
+                parse_css_number("green", c1, 1.0)?,
+                parse_css_number("blue", c2, 1.0)?,
+            ];
+            match space {
+                "srgb" => rgb.map(space::srgb_to_linear),
+                "srgb-linear" => rgb,
+                "display-p3" => space::display_p3_to_linear_srgb(rgb),
+                other => return Err(ColorError::UnsupportedColorSpace(other.to_string())),
+            }
+        }
+        (other, _) => return Err(ColorError::UnsupportedColorSpace(format!("{other}()"))),
+    };
+    Ok(from_linear(linear, alpha))
+}
+
+impl Color {
+    // Constructor from CSS `hsl()` / `hsla()` notation. Any alpha is
+    // accepted and dropped.
+    pub fn from_hsl_css(s: &str) -> Result<Color, ColorError> {
+        let function = parse_function(s)?;
+        if function.name != "hsl" && function.name != "hsla" {
+            return Err(ColorError::Syntax(format!("`{s}` is not an hsl() color")));
+        }
+        if function.components.len() != 3 {
+            return Err(ColorError::Syntax(format!(
+                "hsl() takes 3 components, got {}",
+                function.components.len()
+            )));
+        }
+        if let Some(alpha) = function.alpha {
+            parse_css_alpha(alpha)?;
+        }
+        let hue = parse_css_angle(function.components[0])?;
+        let saturation = parse_hsl_percentage("saturation", function.components[1])?;
+        let lightness = parse_hsl_percentage("lightness", function.components[2])?;
+        Ok(Color::from_hsl(hue, saturation, lightness))
+    }
+
+    #[allow(dead_code)]
+    // Constructor from CSS `lab()`. L may be a number or a percentage of
+    // 100, and a/b numbers or percentages of 125. Colors outside sRGB are
+    // clipped and any alpha is dropped.
+    pub fn from_css_lab_str(s: &str) -> Result<Color, ColorError> {
+        if parse_function(s)?.name != "lab" {
+            return Err(ColorError::Syntax(format!("`{s}` is not a lab() color")));
+        }
+        parse_css_color(s).map(|parsed| parsed.color)
+    }
+
+    #[allow(dead_code)]
+    // CSS `lab(L% a b)` notation, D50 relative as CSS requires
+    pub fn to_css_lab_string(&self) -> String {
+        let (l, a, b) = self.to_lab_with_white(WhitePoint::D50);
+        let round = |v: f32| (v * 100.0).round() / 100.0 + 0.0;
+        format!("lab({}% {} {})", round(l), round(a), round(b))
+    }
+
+    #[allow(dead_code)]
+    // How gray the color is, from 0 (fully saturated) to 1 (neutral),
+    // taken from HSL saturation
+    pub fn relative_grayscale_value(&self) -> f32 {
+        let (_, s, _) = self.to_hsl();
+        (1.0 - s).clamp(0.0, 1.0)
+    }
+
+    #[allow(dead_code)]
+    // CSS filter `grayscale(N%)` with N = (1 - HSL saturation) * 100, so a
+    // colored icon can be shown as gray as this color is
+    pub fn to_css_filter_grayscale(&self) -> String {
+        format!("grayscale({:.0}%)", self.relative_grayscale_value() * 100.0)
+    }
+
+    // CSS Houdini paint worklet color:
+    // `new Color('srgb', [R, G, B], A)` with channels in [0, 1]
+    pub fn to_css_houdini_paint(&self) -> String {
+        format!(
+            "new Color('srgb', [{:.4}, {:.4}, {:.4}], 1)",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0
+        )
+    }
+
+    // Custom properties for a paint worklet to read the color from: a
+    // registered `<color>` property `--NAME`, plus `--NAME-r`, `-g`, `-b`
+    // and `-a` as numbers in [0, 1] for worklets that want channels
+    pub fn to_css_houdini_vars(&self, name: &str) -> String {
+        let name = name.trim().trim_start_matches("--");
+        let hex = self.to_hex();
+        let mut out = format!(
+            "@property --{name} {{\n  syntax: '<color>';\n  inherits: false;\n  initial-value: {hex};\n}}\n\n"
+        );
+        out.push_str(&format!(":root {{\n  --{name}: {hex};\n"));
+        for (suffix, channel) in [("r", self.r), ("g", self.g), ("b", self.b)] {
+            out.push_str(&format!(
+                "  --{name}-{suffix}: {:.4};\n",
+                f32::from(channel) / 255.0
+            ));
+        }
+        out.push_str(&format!("  --{name}-a: 1;\n}}\n"));
+        out
+    }
+
+    #[allow(dead_code)]
+    // `rgb()` built from the custom properties `--NAME-r`, `-g` and `-b`,
+    // which should hold 0-255 numbers
+    pub fn to_css_channel_var_refs(name: &str) -> String {
+        let name = name.trim().trim_start_matches("--");
+        format!("rgb(var(--{name}-r), var(--{name}-g), var(--{name}-b))")
+    }
+
+    #[allow(dead_code)]
+    // `hsl()` built from the custom properties `--NAME-h` (a hue),
+    // `--NAME-s` and `--NAME-l` (percentages)
+    pub fn to_css_hsl_var_refs(name: &str) -> String {
+        let name = name.trim().trim_start_matches("--");
+        format!("hsl(var(--{name}-h), var(--{name}-s), var(--{name}-l))")
+    }
+
+    #[allow(dead_code)]
+    // Like `to_css_channel_var_refs`, with this color's channels as the
+    // `var()` fallbacks for properties that are not set
+    pub fn to_css_channel_var_refs_with_fallback(&self, name: &str) -> String {
+        let name = name.trim().trim_start_matches("--");
+        format!(
+            "rgb(var(--{name}-r, {}), var(--{name}-g, {}), var(--{name}-b, {}))",
+            self.r, self.g, self.b
+        )
+    }
+
+    #[allow(dead_code)]
+    // Like `to_css_hsl_var_refs`, with this color's HSL components (hue in
+    // degrees, rounded to one decimal place) as the `var()` fallbacks
+    pub fn to_css_hsl_var_refs_with_fallback(&self, name: &str) -> String {
+        let name = name.trim().trim_start_matches("--");
+        let round = |v: f32| (v * 10.0).round() / 10.0 + 0.0;
+        let (h, s, l) = self.to_hsl();
+        format!(
+            "hsl(var(--{name}-h, {}), var(--{name}-s, {}%), var(--{name}-l, {}%))",
+            round(h),
+            round(s * 100.0),
+            round(l * 100.0)
+        )
+    }
+}
+
+// Interpolation spaces CSS `color-mix()` accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixSpace {
+    Srgb,
+    SrgbLinear,
+    Lab,
+    Oklab,
+    Xyz,
+    Hsl(HueDirection),
+    Hwb(HueDirection),
+    Lch(HueDirection),
+    Oklch(HueDirection),
+}
+
+impl MixSpace {
+    // The `in ...` clause of `color-mix()`. The default shorter hue
+    // direction is left implicit.
+    pub fn css(&self) -> String {
+        let (name, direction) = match self {
+            MixSpace::Srgb => ("srgb", None),
+            MixSpace::SrgbLinear => ("srgb-linear", None),
+            MixSpace::Lab => ("lab", None),
+            MixSpace::Oklab => ("oklab", None),
+            MixSpace::Xyz => ("xyz", None),
+            MixSpace::Hsl(d) => ("hsl", Some(d)),
+            MixSpace::Hwb(d) => ("hwb", Some(d)),
+            MixSpace::Lch(d) => ("lch", Some(d)),
+            MixSpace::Oklch(d) => ("oklch", Some(d)),
+        };
+        let direction = match direction {
+            None | Some(HueDirection::Shorter) => "",
+            Some(HueDirection::Longer) => " longer hue",
+            Some(HueDirection::Increasing) => " increasing hue",
+            Some(HueDirection::Decreasing) => " decreasing hue",
+        };
+        format!("in {name}{direction}")
+    }
+}
+
+impl FromStr for MixSpace {
+    type Err = String;
+
+    // Accepts the CSS space names, with `-shorter`, `-longer`,
+    // `-increasing` or `-decreasing` after a polar space
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let (name, direction) = match lower.rsplit_once('-') {
+            Some((name, "shorter")) => (name, Some(HueDirection::Shorter)),
+            Some((name, "longer")) => (name, Some(HueDirection::Longer)),
+            Some((name, "increasing")) => (name, Some(HueDirection::Increasing)),
+            Some((name, "decreasing")) => (name, Some(HueDirection::Decreasing)),
+            _ => (lower.as_str(), None),
+        };
+        let hue = direction.unwrap_or_default();
+        let space = match name {
+            "srgb" => MixSpace::Srgb,
+            "srgb-linear" => MixSpace::SrgbLinear,
+            "lab" => MixSpace::Lab,
+            "oklab" => MixSpace::Oklab,
+            "xyz" => MixSpace::Xyz,
+            "hsl" => MixSpace::Hsl(hue),
+            "hwb" => MixSpace::Hwb(hue),
+            "lch" => MixSpace::Lch(hue),
+            "oklch" => MixSpace::Oklch(hue),
+            _ => return Err(format!("unknown color-mix() space `{s}`")),
+        };
+        let polar = matches!(
+            space,
+            MixSpace::Hsl(_) | MixSpace::Hwb(_) | MixSpace::Lch(_) | MixSpace::Oklch(_)
+        );
+        if direction.is_some() && !polar {
+            return Err(format!("`{name}` has no hue to take a direction"));
+        }
+        Ok(space)
+    }
+}
+
+// CSS Color 5 `color-mix()` taking `t_percent` (clamped to 100) of `a`
+// and the rest of `b`, e.g. `color-mix(in oklab, #FF0000 25%, #0000FF)`
+pub fn color_mix_expression(a: &Color, b: &Color, t_percent: u8, space: MixSpace) -> String {
+    format!(
+        "color-mix({}, {} {}%, {})",
+        space.css(),
+        a.to_hex(),
+        t_percent.min(100),
+        b.to_hex()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_angle(s: &str, expected: f32) {
+        let degrees = parse_css_angle(s).unwrap();
+        assert!((degrees - expected).abs() < 0.05, "{s} gave {degrees}");
+    }
+
+    #[test]
+    fn css_angles_convert_every_unit_to_degrees() {
+        assert_angle("200deg", 200.0);
+        assert_angle("200grad", 180.0);
+        assert_angle("3.14rad", 179.91);
+        assert_angle("0.5turn", 180.0);
+        assert_angle("200", 200.0);
+        assert_angle(" 90DEG ", 90.0);
+    }
+
+    #[test]
+    fn css_angles_wrap_into_one_turn() {
+        assert_angle("360deg", 0.0);
+        assert_angle("370deg", 10.0);
+        assert_angle("-30", 330.0);
+        assert_angle("1.25turn", 90.0);
+        assert_angle("-100grad", 270.0);
+    }
+
+    #[test]
+    fn css_angles_reject_garbage() {
+        for bad in ["", "deg", "12px", "infdeg", "NaN"] {
+            assert!(parse_css_angle(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn hsl_css_accepts_angle_units() {
+        let red = Color::from_rgb(255, 0, 0);
+        let cyan = Color::from_rgb(0, 255, 255);
+        assert_eq!(
+            Color::from_hsl_css("hsl(0.5turn 100% 50%)"),
+            Ok(cyan.clone())
+        );
+        assert_eq!(Color::from_hsl_css("hsl(200grad, 100%, 50%)"), Ok(cyan));
+        assert_eq!(Color::from_hsl_css("hsl(720deg 100% 50%)"), Ok(red.clone()));
+        assert_eq!(Color::from_hsl_css("hsla(-360, 100%, 50%, 0.5)"), Ok(red));
+    }
+
+    fn parsed(s: &str) -> (Color, f32, bool) {
+        let css = parse_css_color(s).unwrap();
+        (css.color, css.alpha, css.clipped)
+    }
+
+    #[test]
+    fn css_color_4_functions_parse() {
+        let red = Color::from_rgb(255, 0, 0);
+        assert_eq!(
+            parsed("oklch(62.8% 0.258 29.23)"),
+            (red.clone(), 1.0, false)
+        );
+        assert_eq!(
+            parsed("oklab(0.628 0.2249 0.1258)"),
+            (red.clone(), 1.0, false)
+        );
+        assert_eq!(parsed("lch(54.29 106.84 40.85)"), (red.clone(), 1.0, false));
+        assert_eq!(
+            parsed("lab(52.2% 40.1 59.9)"),
+            (Color::from_rgb(198, 93, 7), 1.0, false)
+        );
+        assert_eq!(
+            parsed("color(srgb-linear 1 0.2140 0)"),
+            (Color::from_rgb(255, 127, 0), 1.0, false)
+        );
+    }
+
+    #[test]
+    fn css_percentages_scale_per_component() {
+        // L is a percentage of 100 in lab() and of 1 in oklch(), a/b of 125
+        assert_eq!(
+            parsed("lab(52.2 32.08% 47.92%)"),
+            parsed("lab(52.2% 40.1 59.9)")
+        );
+        assert_eq!(
+            parsed("oklch(0.628 0.258 29.23)"),
+            parsed("oklch(62.8% 0.258 29.23)")
+        );
+        assert_eq!(
+            parsed("color(srgb 100% 50% 0%)"),
+            (Color::from_rgb(255, 128, 0), 1.0, false)
+        );
+    }
+
+    #[test]
+    fn css_none_components_count_as_zero() {
+        assert_eq!(
+            parsed("color(srgb 100% none 0)"),
+            (Color::from_rgb(255, 0, 0), 1.0, false)
+        );
+        assert_eq!(
+            parsed("lch(50% 0 none)"),
+            (Color::from_rgb(119, 119, 119), 1.0, false)
+        );
+        assert_eq!(
+            parsed("oklch(1 0 none / none)"),
+            (Color::from_rgb(255, 255, 255), 0.0, false)
+        );
+    }
+
+    #[test]
+    fn css_alpha_clause_is_optional() {
+        assert_eq!(
+            parsed("color(srgb 1 0.5 0 / 50%)"),
+            (Color::from_rgb(255, 128, 0), 0.5, false)
+        );
+        assert_eq!(
+            parsed("lch(50% 0 0 / 0.25)"),
+            (Color::from_rgb(119, 119, 119), 0.25, false)
+        );
+    }
+
+    #[test]
+    fn css_out_of_gamut_colors_are_clipped_and_flagged() {
+        assert_eq!(
+            parsed("color(display-p3 1 0.5 0)"),
+            (Color::from_rgb(255, 118, 0), 1.0, true)
+        );
+        // The D65 Lab coordinates of sRGB red are outside sRGB under D50
+        assert!(parsed("lab(53.24 80.09 67.2)").2);
+    }
+
+    #[test]
+    fn css_errors_name_the_unsupported_space() {
+        assert!(matches!(
+            parse_css_color("color(rec2020 1 0 0)"),
+            Err(ColorError::UnsupportedColorSpace(space)) if space == "rec2020"
+        ));
+        assert!(matches!(
+            parse_css_color("hwb(0 0% 0%)"),
+            Err(ColorError::UnsupportedColorSpace(space)) if space == "hwb()"
+        ));
+        assert!(parse_css_color("lab(50 20)").is_err());
+        assert!(parse_css_color("color(srgb 1 0)").is_err());
+    }
+
+    #[test]
+    fn houdini_paint_uses_the_color_constructor_signature() {
+        for color in crate::palette::web_safe_palette() {
+            let paint = color.to_css_houdini_paint();
+            let (channels, alpha) = paint
+                .strip_prefix("new Color('srgb', [")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|rest| rest.split_once("], "))
+                .unwrap_or_else(|| panic!("{paint}"));
+            assert_eq!(alpha, "1");
+            let channels: Vec<f32> = channels.split(", ").map(|c| c.parse().unwrap()).collect();
+            let rgb = [color.r, color.g, color.b].map(|c| f32::from(c) / 255.0);
+            assert_eq!(channels.len(), 3);
+            for (got, want) in channels.iter().zip(rgb) {
+                assert!((got - want).abs() < 1e-4, "{paint}");
+            }
+        }
+        assert_eq!(
+            Color::from_rgb(255, 128, 0).to_css_houdini_paint(),
+            "new Color('srgb', [1.0000, 0.5020, 0.0000], 1)"
+        );
+    }
+
+    #[test]
+    fn houdini_vars_register_the_color_and_channels() {
+        let vars = Color::from_rgb(255, 128, 0).to_css_houdini_vars("--brand");
+        assert_eq!(
+            vars,
+            "@property --brand {\n  syntax: '<color>';\n  inherits: false;\n  initial-value: #FF8000;\n}\n\n\
+             :root {\n  --brand: #FF8000;\n  --brand-r: 1.0000;\n  --brand-g: 0.5020;\n  --brand-b: 0.0000;\n  --brand-a: 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn color_mix_expression_uses_level_5_syntax() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        assert_eq!(
+            color_mix_expression(&red, &blue, 25, MixSpace::Oklab),
+            "color-mix(in oklab, #FF0000 25%, #0000FF)"
+        );
+        assert_eq!(
+            color_mix_expression(&red, &blue, 200, MixSpace::Oklch(HueDirection::Longer)),
+            "color-mix(in oklch longer hue, #FF0000 100%, #0000FF)"
+        );
+        assert_eq!(
+            color_mix_expression(&red, &blue, 50, MixSpace::Hsl(HueDirection::Shorter)),
+            "color-mix(in hsl, #FF0000 50%, #0000FF)"
+        );
+    }
+
+    #[test]
+    fn color_mix_expression_parses_back() {
+        let (a, b) = (Color::from_rgb(18, 52, 86), Color::from_rgb(250, 10, 130));
+        for name in [
+            "srgb",
+            "srgb-linear",
+            "lab",
+            "xyz",
+            "hwb-increasing",
+            "lch-decreasing",
+        ] {
+            let space: MixSpace = name.parse().unwrap();
+            let expression = color_mix_expression(&a, &b, 30, space);
+            let function = parse_function(&expression).unwrap();
+            assert_eq!(function.name, "color-mix");
+            assert_eq!(function.alpha, None);
+            let [interpolation, first, second] = function.components[..] else {
+                panic!("{expression}");
+            };
+            assert_eq!(interpolation, space.css());
+            let (first_color, percent) = first.split_once(' ').unwrap();
+            assert_eq!(parse_css_color(first_color).unwrap().color, a);
+            assert_eq!(percent, "30%");
+            assert_eq!(parse_css_color(second).unwrap().color, b);
+        }
+        assert!("oklab-longer".parse::<MixSpace>().is_err());
+        assert!("cmyk".parse::<MixSpace>().is_err());
+    }
+
+    #[test]
+    fn grayscale_filter_follows_hsl_saturation() {
+        for gray in [0, 128, 255] {
+            let color = Color::from_rgb(gray, gray, gray);
+            assert_eq!(color.relative_grayscale_value(), 1.0);
+            assert_eq!(color.to_css_filter_grayscale(), "grayscale(100%)");
+        }
+        for saturated in [(255, 0, 0), (0, 128, 255), (64, 0, 0)] {
+            let color = Color::from_rgb(saturated.0, saturated.1, saturated.2);
+            assert_eq!(color.relative_grayscale_value(), 0.0);
+            assert_eq!(color.to_css_filter_grayscale(), "grayscale(0%)");
+        }
+        assert_eq!(
+            Color::from_hsl(200.0, 0.25, 0.5).to_css_filter_grayscale(),
+            "grayscale(75%)"
+        );
+    }
+
+    #[test]
+    fn lab_lightness_percent_and_number_agree() {
+        let percent = Color::from_css_lab_str("lab(50% -25 30)").unwrap();
+        assert_eq!(
+            Color::from_css_lab_str("lab(50 -25 30)"),
+            Ok(percent.clone())
+        );
+        // 100% of a or b is 125
+        assert_eq!(Color::from_css_lab_str("lab(50% -20% 24%)"), Ok(percent));
+        assert_eq!(
+            Color::from_css_lab_str("lab(100% 0 0)"),
+            Ok(Color::from_rgb(255, 255, 255))
+        );
+        assert_eq!(
+            Color::from_css_lab_str("lab(0% 0 0)"),
+            Ok(Color::from_rgb(0, 0, 0))
+        );
+        assert!(Color::from_css_lab_str("rgb(0 0 0)").is_err());
+        assert!(Color::from_css_lab_str("lab(50%)").is_err());
+    }
+
+    #[test]
+    fn lab_string_round_trips() {
+        assert_eq!(
+            Color::from_rgb(255, 255, 255).to_css_lab_string(),
+            "lab(100% 0 0)"
+        );
+        for color in crate::palette::web_safe_palette() {
+            let lab = color.to_css_lab_string();
+            assert_eq!(Color::from_css_lab_str(&lab), Ok(color), "{lab}");
+        }
+    }
+
+    // The arguments of `function(...)` split at top-level commas, or None
+    // if the parentheses do not balance
+    fn call_arguments<'a>(call: &'a str, function: &str) -> Option<Vec<&'a str>> {
+        let inner = call
+            .strip_prefix(function)?
+            .strip_prefix('(')?
+            .strip_suffix(')')?;
+        let mut args = Vec::new();
+        let (mut depth, mut start) = (0i32, 0);
+        for (i, c) in inner.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    args.push(inner[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            if depth < 0 {
+                return None;
+            }
+        }
+        (depth == 0).then(|| {
+            args.push(inner[start..].trim());
+            args
+        })
+    }
+
+    #[test]
+    fn channel_var_refs_name_each_channel() {
+        let rgb = Color::to_css_channel_var_refs("brand");
+        assert_eq!(rgb, "rgb(var(--brand-r), var(--brand-g), var(--brand-b))");
+        assert_eq!(Color::to_css_channel_var_refs("--brand"), rgb);
+        assert_eq!(
+            call_arguments(&rgb, "rgb"),
+            Some(vec!["var(--brand-r)", "var(--brand-g)", "var(--brand-b)"])
+        );
+        let hsl = Color::to_css_hsl_var_refs("brand");
+        assert_eq!(
+            call_arguments(&hsl, "hsl"),
+            Some(vec!["var(--brand-h)", "var(--brand-s)", "var(--brand-l)"])
+        );
+    }
+
+    #[test]
+    fn var_refs_fall_back_to_the_color() {
+        let color = Color::from_rgb(255, 128, 0);
+        let rgb = color.to_css_channel_var_refs_with_fallback("brand");
+        assert_eq!(
+            rgb,
+            "rgb(var(--brand-r, 255), var(--brand-g, 128), var(--brand-b, 0))"
+        );
+        let hsl = color.to_css_hsl_var_refs_with_fallback("--brand");
+        assert_eq!(
+            hsl,
+            "hsl(var(--brand-h, 30.1), var(--brand-s, 100%), var(--brand-l, 50%))"
+        );
+        // With every fallback substituted the calls parse back to the color
+        for (call, function) in [(&rgb, "rgb"), (&hsl, "hsl")] {
+            let args = call_arguments(call, function).unwrap();
+            let resolved: Vec<&str> = args
+                .iter()
+                .map(|arg| {
+                    let (_, fallback) = arg
+                        .strip_prefix("var(")
+                        .and_then(|a| a.strip_suffix(')'))
+                        .and_then(|a| a.split_once(", "))
+                        .unwrap_or_else(|| panic!("{arg}"));
+                    fallback
+                })
+                .collect();
+            let css = format!("{function}({})", resolved.join(", "));
+            let parsed = parse_css_color(&css).unwrap().color;
+            for (got, want) in [
+                (parsed.r, color.r),
+                (parsed.g, color.g),
+                (parsed.b, color.b),
+            ] {
+                assert!(got.abs_diff(want) <= 1, "{css}: {parsed:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+// Errors raised while reading a document such as a palette file or ICC profile
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    // The document itself is not well formed
+    Malformed(String),
+    // The document is fine but one of its entries is not a usable color
+    InvalidEntry { index: usize, reason: String },
+    // The document is valid but uses a feature we don't handle
+    Unsupported(String),
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::Malformed(reason) => write!(f, "malformed document: {reason}"),
+            ColorParseError::InvalidEntry { index, reason } => {
+                write!(f, "invalid entry {index}: {reason}")
+            }
+            ColorParseError::Unsupported(reason) => write!(f, "unsupported: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+// Errors raised while reading a single color value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    // The text does not follow the expected syntax
+    Syntax(String),
+    // A component is outside the range its format allows
+    OutOfRange { component: &'static str, value: f32 },
+    // A blend weight (or the total of all weights) that is not positive
+    InvalidWeight(f32),
+    // The value names a color space or function we can't convert from
+    UnsupportedColorSpace(String),
+    // A hex code without exactly six digits
+    InvalidHexLength { len: usize },
+    // A character that is not a hex digit, counted from after the `#`
+    InvalidHexDigit { position: usize },
+    // A byte buffer too small to hold a color
+    SliceTooShort { expected: usize, got: usize },
+    // A hex code with lowercase digits where uppercase is required
+    NotUppercase,
+    // A hex code with uppercase digits where lowercase is required
+    NotLowercase,
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::Syntax(reason) => write!(f, "{reason}"),
+            ColorError::OutOfRange { component, value } => {
+                write!(f, "{component} component {value} is out of range")
+            }
+            ColorError::InvalidWeight(weight) => {
+                write!(f, "weights must be positive, got {weight}")
+            }
+            ColorError::UnsupportedColorSpace(space) => {
+                write!(f, "unsupported color space `{space}`")
+            }
+            ColorError::InvalidHexLength { len } => {
+                write!(f, "hex code must be 6 digits long, got {len}")
+            }
+            ColorError::InvalidHexDigit { position } => {
+                write!(f, "invalid hex digit at position {position}")
+            }
+            ColorError::SliceTooShort { expected, got } => {
+                write!(f, "need at least {expected} bytes, got {got}")
+            }
+            ColorError::NotUppercase => write!(f, "hex code must be uppercase"),
+            ColorError::NotLowercase => write!(f, "hex code must be lowercase"),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
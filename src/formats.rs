@@ -0,0 +1,1847 @@
+use crate::css;
+use crate::error::{ColorError, ColorParseError};
+use crate::json;
+use crate::mix::InterpolationSpace;
+use crate::space;
+use crate::xml::{self, Token};
+use crate::Color;
+use std::str::FromStr;
+
+// The color space an Xcode color set stores its components in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayGamut {
+    Srgb,
+    DisplayP3,
+}
+
+impl DisplayGamut {
+    // The `color-space` value Xcode writes
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayGamut::Srgb => "srgb",
+            DisplayGamut::DisplayP3 => "display-p3",
+        }
+    }
+}
+
+impl FromStr for DisplayGamut {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "srgb" => Ok(DisplayGamut::Srgb),
+            "display-p3" | "p3" => Ok(DisplayGamut::DisplayP3),
+            _ => Err(format!(
+                "unknown display gamut `{s}` (expected srgb or display-p3)"
+            )),
+        }
+    }
+}
+
+// The value of `attr` on the first `local_name` element (with any
+// namespace prefix) in an XML fragment
+fn first_element_attr(xml: &str, local_name: &str, attr: &str) -> Result<String, ColorError> {
+    let tokens = xml::tokenize(xml).map_err(ColorError::Syntax)?;
+    tokens
+        .iter()
+        .find_map(|token| match token {
+            Token::Open { name, .. } if name.rsplit(':').next() == Some(local_name) => {
+                Some(token.attr(attr))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| ColorError::Syntax(format!("no <{local_name}> element")))?
+        .map(str::to_string)
+        .ok_or_else(|| ColorError::Syntax(format!("<{local_name}> has no `{attr}` attribute")))
+}
+
+// Windows default colors for the OOXML `sysClr` values (Windows 10
+// defaults), used when the element carries no `lastClr`
+const OOXML_SYSTEM_COLORS: [(&str, &str); 23] = [
+    ("windowText", "000000"),
+    ("window", "FFFFFF"),
+    ("menu", "F0F0F0"),
+    ("menuText", "000000"),
+    ("btnFace", "F0F0F0"),
+    ("btnText", "000000"),
+    ("btnShadow", "A0A0A0"),
+    ("btnHighlight", "FFFFFF"),
+    ("highlight", "0078D7"),
+    ("highlightText", "FFFFFF"),
+    ("grayText", "6D6D6D"),
+    ("captionText", "000000"),
+    ("activeCaption", "99B4D1"),
+    ("inactiveCaption", "BFCDDB"),
+    ("windowFrame", "646464"),
+    ("3dDkShadow", "696969"),
+    ("3dLight", "E3E3E3"),
+    ("infoText", "000000"),
+    ("infoBk", "FFFFE1"),
+    ("hotLight", "0066CC"),
+    ("scrollBar", "C8C8C8"),
+    ("background", "000000"),
+    ("appWorkspace", "ABABAB"),
+];
+
+// Operands of a postfix operator such as PDF `r g b rg` or PostScript
+// `r g b setrgbcolor`: the numbers
+// before `operator` (one of `operators`), each a channel in [0, 1]
+fn postfix_channels(s: &str, operators: &[&str], count: usize) -> Result<Vec<u8>, ColorError> {
+    let invalid = || {
+        ColorError::Syntax(format!(
+            "`{s}` is not a `{}` operation",
+            operators.join("` or `")
+        ))
+    };
+    let mut words: Vec<&str> = s.split_whitespace().collect();
+    if !words.pop().is_some_and(|op| operators.contains(&op)) || words.len() != count {
+        return Err(invalid());
+    }
+    let names = ["red", "green", "blue"];
+    words
+        .iter()
+        .zip(names)
+        .map(|(word, name)| unit_channel(name, word.parse().map_err(|_| invalid())?))
+        .collect()
+}
+
+// The contents of consecutive `{...}` groups, e.g. the arguments of a LaTeX
+// command. Groups do not nest.
+fn brace_groups(s: &str) -> Option<Vec<&str>> {
+    let mut groups = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let inner = rest.strip_prefix('{')?;
+        let end = inner.find('}')?;
+        groups.push(inner[..end].trim());
+        rest = inner[end + 1..].trim_start();
+    }
+    Some(groups)
+}
+
+// A Highcharts `colorAxis.stops` array blending `from` into `to` over
+// `steps` evenly spaced stops (at least two), e.g.
+// `[[0, "#000000"], [0.5, "#808080"], [1, "#FFFFFF"]]`
+pub fn highcharts_color_axis(from: &Color, to: &Color, steps: usize) -> String {
+    let last = steps.max(2) - 1;
+    let stops: Vec<String> = (0..=last)
+        .map(|i| {
+            let t = i as f64 / last as f64;
+            let color = Color::interpolate(from, to, t as f32, InterpolationSpace::Srgb);
+            format!(
+                "[{}, \"{}\"]",
+                (t * 10_000.0).round() / 10_000.0,
+                color.to_hex()
+            )
+        })
+        .collect();
+    format!("[{}]", stops.join(", "))
+}
+
+// Packed 32-bit SDL2 pixel formats, as returned by `SDL_MapRGB(A)`
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormatKind {
+    // 0x00RRGGBB, the top byte is unused
+    Rgb888,
+    // 0xAARRGGBB
+    Argb8888,
+    // 0xRRGGBBAA
+    Rgba8888,
+}
+
+// An Xcode color component: a float string such as "0.500", an 8-bit
+// integer string such as "128", or a hex byte such as "0x80". Plain JSON
+// numbers are read as floats.
+fn xcode_component(
+    index: usize,
+    name: &str,
+    value: Option<&json::Json>,
+) -> Result<f64, ColorParseError> {
+    let invalid = |reason: String| ColorParseError::InvalidEntry { index, reason };
+    let value = value.ok_or_else(|| invalid(format!("missing `{name}` component")))?;
+    let unit = match (value.as_f64(), value.as_str().map(str::trim)) {
+        (Some(n), _) => n,
+        (None, Some(s)) => {
+            if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                u8::from_str_radix(hex, 16)
+                    .map(|byte| f64::from(byte) / 255.0)
+                    .map_err(|_| invalid(format!("invalid `{name}` component `{s}`")))?
+            } else if s.contains('.') {
+                s.parse()
+                    .map_err(|_| invalid(format!("invalid `{name}` component `{s}`")))?
+            } else {
+                s.parse::<u8>()
+                    .map(|byte| f64::from(byte) / 255.0)
+                    .map_err(|_| invalid(format!("invalid `{name}` component `{s}`")))?
+            }
+        }
+        (None, None) => return Err(invalid(format!("`{name}` is not a number or string"))),
+    };
+    if (0.0..=1.0).contains(&unit) {
+        Ok(unit)
+    } else {
+        Err(invalid(format!(
+            "`{name}` component {unit} is out of range"
+        )))
+    }
+}
+
+#[allow(dead_code)]
+// Read the first color of an Xcode `.colorset` `Contents.json`, returning
+// it with its alpha and the gamut it was stored in. Display P3 components
+// are converted to sRGB and clipped.
+pub fn parse_xcode_color_json(text: &str) -> Result<(Color, f32, DisplayGamut), ColorParseError> {
+    let document = json::parse(text).map_err(ColorParseError::Malformed)?;
+    let entry = document
+        .get("colors")
+        .and_then(json::Json::as_array)
+        .and_then(|colors| colors.first())
+        .ok_or_else(|| {
+            ColorParseError::Malformed("expected a non-empty `colors` array".to_string())
+        })?;
+    let color = entry
+        .get("color")
+        .ok_or_else(|| ColorParseError::InvalidEntry {
+            index: 0,
+            reason: "missing `color`".to_string(),
+        })?;
+    let gamut = match color.get("color-space").and_then(json::Json::as_str) {
+        Some(name) => name
+            .parse()
+            .map_err(|_| ColorParseError::Unsupported(format!("color space `{name}`")))?,
+        None => DisplayGamut::Srgb,
+    };
+    let components = color.get("components");
+    let component = |name| xcode_component(0, name, components.and_then(|c| c.get(name)));
+    let (r, g, b) = (component("red")?, component("green")?, component("blue")?);
+    let alpha = match components.and_then(|c| c.get("alpha")) {
+        Some(_) => component("alpha")? as f32,
+        None => 1.0,
+    };
+
+    let color = match gamut {
+        DisplayGamut::Srgb => Color::from_rgb(
+            (r * 255.0).round() as u8,
+            (g * 255.0).round() as u8,
+            (b * 255.0).round() as u8,
+        ),
+        DisplayGamut::DisplayP3 => Color::from_linear_rgb(space::display_p3_to_linear_srgb([
+            r as f32, g as f32, b as f32,
+        ])),
+    };
+    Ok((color, alpha, gamut))
+}
+
+// Parse a channel given as a float in [0, 1]
+fn unit_channel(component: &'static str, value: f64) -> Result<u8, ColorError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok((value * 255.0).round() as u8)
+    } else {
+        Err(ColorError::OutOfRange {
+            component,
+            value: value as f32,
+        })
+    }
+}
+
+// Labels NSColor initializers use for the red argument
+const NSCOLOR_RED_LABELS: [&str; 4] = ["red", "srgbRed", "calibratedRed", "deviceRed"];
+
+// Parse `label: value` arguments of an NSColor initializer into the color
+// and alpha, checking the labels are red, green, blue and alpha in order
+fn nscolor_components<'a>(
+    s: &str,
+    args: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<(Color, f32), ColorError> {
+    let invalid = || ColorError::Syntax(format!("`{s}` is not an NSColor expression"));
+    let args: Vec<(&str, &str)> = args.collect();
+    if args.len() != 4 {
+        return Err(invalid());
+    }
+    let expected = ["green", "blue", "alpha"];
+    if !NSCOLOR_RED_LABELS.contains(&args[0].0)
+        || args[1..]
+            .iter()
+            .zip(expected)
+            .any(|((label, _), want)| *label != want)
+    {
+        return Err(invalid());
+    }
+    let mut values = [0.0; 4];
+    for (value, (_, text)) in values.iter_mut().zip(&args) {
+        let text = text.trim();
+        // Objective-C float literals may carry an `f` suffix
+        let text = text.strip_suffix(['f', 'F']).unwrap_or(text);
+        *value = text.parse::<f64>().map_err(|_| invalid())?;
+    }
+    let color = Color::from_rgb(
+        unit_channel("red", values[0])?,
+        unit_channel("green", values[1])?,
+        unit_channel("blue", values[2])?,
+    );
+    if !(0.0..=1.0).contains(&values[3]) {
+        return Err(ColorError::OutOfRange {
+            component: "alpha",
+            value: values[3] as f32,
+        });
+    }
+    Ok((color, values[3] as f32))
+}
+
+impl Color {
+    // Lottie color value: `[r, g, b, a]` with floats in [0, 1]
+    pub fn to_lottie_color(&self, alpha: f32) -> String {
+        format!(
+            "[{:.4}, {:.4}, {:.4}, {:.4}]",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0,
+            alpha.clamp(0.0, 1.0)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a Lottie color value, returning the color and alpha.
+    // A missing alpha component means fully opaque.
+    pub fn from_lottie_color(s: &str) -> Result<(Color, f32), ColorError> {
+        let value = json::parse(s).map_err(ColorError::Syntax)?;
+        let components: Vec<f64> = value
+            .as_array()
+            .and_then(|items| items.iter().map(json::Json::as_f64).collect())
+            .ok_or_else(|| ColorError::Syntax(format!("`{s}` is not an array of numbers")))?;
+        if !(3..=4).contains(&components.len()) {
+            return Err(ColorError::Syntax(format!(
+                "expected 3 or 4 components, got {}",
+                components.len()
+            )));
+        }
+
+        let color = Color::from_rgb(
+            unit_channel("red", components[0])?,
+            unit_channel("green", components[1])?,
+            unit_channel("blue", components[2])?,
+        );
+        let alpha = match components.get(3) {
+            Some(&a) if (0.0..=1.0).contains(&a) => a as f32,
+            Some(&a) => {
+                return Err(ColorError::OutOfRange {
+                    component: "alpha",
+                    value: a as f32,
+                })
+            }
+            None => 1.0,
+        };
+        Ok((color, alpha))
+    }
+
+    #[allow(dead_code)]
+    // R hex string with alpha, as returned by `rgb(..., alpha)`: `#RRGGBBAA`
+    pub fn to_r_hex(&self, alpha: f32) -> String {
+        let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!("{}{alpha:02X}", self.to_hex())
+    }
+
+    #[allow(dead_code)]
+    // R call producing this color, channels in [0, 1]. Alpha is only passed
+    // when the color is not fully opaque.
+    pub fn to_r_rgb_call(&self, alpha: f32) -> String {
+        let (r, g, b) = (
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0,
+        );
+        if alpha >= 1.0 {
+            format!("rgb({r:.3}, {g:.3}, {b:.3})")
+        } else {
+            format!("rgb({r:.3}, {g:.3}, {b:.3}, {:.3})", alpha.max(0.0))
+        }
+    }
+
+    #[allow(dead_code)]
+    // Constructor from an R hex string, `#RRGGBBAA` or `#RRGGBB`
+    pub fn from_r_hex(s: &str) -> Result<(Color, f32), ColorError> {
+        let digits = s.trim().trim_start_matches('#');
+        if !digits.is_ascii() || !(digits.len() == 6 || digits.len() == 8) {
+            return Err(ColorError::Syntax(format!(
+                "`{s}` is not an R color (#RRGGBB or #RRGGBBAA)"
+            )));
+        }
+        let color =
+            Color::from_hex(&digits[..6]).map_err(|e| ColorError::Syntax(format!("`{s}`: {e}")))?;
+        let alpha = match digits.get(6..) {
+            Some(aa) if !aa.is_empty() => {
+                let byte = u8::from_str_radix(aa, 16)
+                    .map_err(|_| ColorError::Syntax(format!("`{s}`: invalid alpha digits")))?;
+                f32::from(byte) / 255.0
+            }
+            _ => 1.0,
+        };
+        Ok((color, alpha))
+    }
+
+    // Swift `NSColor` initializer, channels in [0, 1]
+    pub fn to_nscolor_swift(&self, alpha: f32) -> String {
+        format!(
+            "NSColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.3})",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0,
+            alpha.clamp(0.0, 1.0)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a Swift `NSColor(red:green:blue:alpha:)` expression,
+    // returning the color and alpha. The `srgbRed`, `calibratedRed` and
+    // `deviceRed` variants are accepted too.
+    pub fn from_nscolor_swift(s: &str) -> Result<(Color, f32), ColorError> {
+        let args = s
+            .trim()
+            .strip_prefix("NSColor(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or_else(|| ColorError::Syntax(format!("`{s}` is not an NSColor expression")))?;
+        let args = args
+            .split(',')
+            .map(|arg| arg.split_once(':').unwrap_or(("", arg)))
+            .map(|(label, value)| (label.trim(), value));
+        nscolor_components(s, args)
+    }
+
+    // Objective-C `NSColor` message, channels in [0, 1]
+    pub fn to_nscolor_objc(&self, alpha: f32) -> String {
+        format!(
+            "[NSColor colorWithRed:{:.3} green:{:.3} blue:{:.3} alpha:{:.3}]",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0,
+            alpha.clamp(0.0, 1.0)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from an Objective-C
+    // `[NSColor colorWithRed:green:blue:alpha:]` message, returning the
+    // color and alpha
+    pub fn from_nscolor_objc(s: &str) -> Result<(Color, f32), ColorError> {
+        let args = s
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|rest| rest.trim_start().strip_prefix("NSColor"))
+            .ok_or_else(|| ColorError::Syntax(format!("`{s}` is not an NSColor expression")))?;
+        let mut args: Vec<(&str, &str)> = args
+            .split_whitespace()
+            .map(|arg| arg.split_once(':').unwrap_or(("", arg)))
+            .collect();
+        // Map the selector's first part onto the Swift label
+        if let Some((label, _)) = args.first_mut() {
+            *label = match *label {
+                "colorWithRed" => "red",
+                "colorWithSRGBRed" => "srgbRed",
+                "colorWithCalibratedRed" => "calibratedRed",
+                "colorWithDeviceRed" => "deviceRed",
+                _ => "",
+            };
+        }
+        nscolor_components(s, args.into_iter())
+    }
+
+    // `Contents.json` for an Xcode asset catalog `.colorset` holding this
+    // color. Components are written as normalized float strings, converted
+    // to P3 for the display-p3 gamut. Four decimals are enough for 8-bit
+    // channels to survive the round trip through P3.
+    pub fn to_xcode_color_json(&self, alpha: f32, display_gamut: DisplayGamut) -> String {
+        let [r, g, b] = match display_gamut {
+            DisplayGamut::Srgb => [self.r, self.g, self.b].map(|c| f32::from(c) / 255.0),
+            DisplayGamut::DisplayP3 => self.to_display_p3(),
+        };
+        format!(
+            r#"{{
+  "colors" : [
+    {{
+      "color" : {{
+        "color-space" : "{}",
+        "components" : {{
+          "alpha" : "{:.4}",
+          "blue" : "{b:.4}",
+          "green" : "{g:.4}",
+          "red" : "{r:.4}"
+        }}
+      }},
+      "idiom" : "universal"
+    }}
+  ],
+  "info" : {{
+    "author" : "xcode",
+    "version" : 1
+  }}
+}}
+"#,
+            display_gamut.name(),
+            alpha.clamp(0.0, 1.0)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from the fields of an `sdl2::pixels::Color`, returning
+    // the color and its alpha
+    pub fn from_sdl2_tuple(r: u8, g: u8, b: u8, a: u8) -> (Color, u8) {
+        (Color::from_rgb(r, g, b), a)
+    }
+
+    #[allow(dead_code)]
+    // Fields of an `sdl2::pixels::Color`: `(r, g, b, a)`
+    pub fn to_sdl2_tuple(&self, alpha: u8) -> (u8, u8, u8, u8) {
+        (self.r, self.g, self.b, alpha)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a pixel value mapped with `SDL_MapRGB(A)` in the
+    // given format. Alpha, if any, is dropped.
+    pub fn from_sdl2_mapped(mapped: u32, format: PixelFormatKind) -> Color {
+        // Most significant byte first
+        let [first, second, third, fourth] = mapped.to_be_bytes();
+        match format {
+            PixelFormatKind::Rgb888 | PixelFormatKind::Argb8888 => {
+                Color::from_rgb(second, third, fourth)
+            }
+            PixelFormatKind::Rgba8888 => Color::from_rgb(first, second, third),
+        }
+    }
+
+    #[allow(dead_code)]
+    // OpenType COLR/CPAL color record: `[b, g, r, a]`
+    pub fn to_opentype_bgra(&self, alpha: u8) -> [u8; 4] {
+        [self.b, self.g, self.r, alpha]
+    }
+
+    #[allow(dead_code)]
+    // Constructor from an OpenType CPAL color record, returning the color
+    // and its alpha
+    pub fn from_opentype_bgra(arr: [u8; 4]) -> (Color, u8) {
+        let [b, g, r, a] = arr;
+        (Color::from_rgb(r, g, b), a)
+    }
+
+    #[allow(dead_code)]
+    // Bevy color expression with linear-light channels (six decimals),
+    // e.g. `Color::rgb_linear(1.000000, 0.214041, 0.000000)`. Bevy's plain
+    // `Color::rgb` takes sRGB values, so the linear constructor is used.
+    pub fn to_bevy_linear_rgb_string(&self) -> String {
+        let [r, g, b] = self.to_linear_rgb();
+        format!("Color::rgb_linear({r:.6}, {g:.6}, {b:.6})")
+    }
+
+    #[allow(dead_code)]
+    // Bevy color expression with 8-bit sRGB channels: `Color::rgb_u8(R, G, B)`
+    pub fn to_bevy_srgb_string(&self) -> String {
+        format!("Color::rgb_u8({}, {}, {})", self.r, self.g, self.b)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a Bevy color expression: `Color::rgb`,
+    // `Color::rgb_linear` or `Color::rgb_u8`, or their `rgba` forms whose
+    // alpha is checked and dropped
+    pub fn from_bevy_rgb_string(s: &str) -> Result<Color, ColorError> {
+        let invalid = || ColorError::Syntax(format!("`{s}` is not a Bevy color expression"));
+        let rest = s.trim().strip_prefix("Color::").ok_or_else(invalid)?;
+        let open = rest.find('(').ok_or_else(invalid)?;
+        let (constructor, args) = (&rest[..open], &rest[open..]);
+        let args = args
+            .strip_prefix('(')
+            .and_then(|args| args.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let args: Vec<&str> = args.split(',').map(str::trim).collect();
+
+        let (kind, has_alpha) = match constructor {
+            "rgb" | "rgb_linear" | "rgb_u8" => (constructor, false),
+            "rgba" => ("rgb", true),
+            "rgba_linear" => ("rgb_linear", true),
+            "rgba_u8" => ("rgb_u8", true),
+            _ => return Err(invalid()),
+        };
+        if args.len() != 3 + usize::from(has_alpha) {
+            return Err(invalid());
+        }
+        if kind == "rgb_u8" {
+            let bytes: Vec<u8> = args
+                .iter()
+                .map(|a| a.parse::<u8>().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()?;
+            return Ok(Color::from_rgb(bytes[0], bytes[1], bytes[2]));
+        }
+
+        let values: Vec<f64> = args
+            .iter()
+            .map(|a| a.parse::<f64>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let names = ["red", "green", "blue", "alpha"];
+        for (name, &value) in names.iter().zip(&values) {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ColorError::OutOfRange {
+                    component: name,
+                    value: value as f32,
+                });
+            }
+        }
+        Ok(if kind == "rgb_linear" {
+            Color::from_linear_rgb([values[0] as f32, values[1] as f32, values[2] as f32])
+        } else {
+            Color::from_rgb(
+                unit_channel("red", values[0])?,
+                unit_channel("green", values[1])?,
+                unit_channel("blue", values[2])?,
+            )
+        })
+    }
+
+    // Excel ARGB color: `AARRGGBB`, alpha first and no `#`
+    pub fn to_excel_argb_hex(&self, alpha: u8) -> String {
+        format!("{alpha:02X}{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    // Constructor from an Excel `AARRGGBB` color, returning the color and
+    // alpha
+    pub fn from_excel_argb_hex(s: &str) -> Result<(Color, u8), ColorError> {
+        let s = s.trim();
+        if !s.is_ascii() || s.len() != 8 {
+            return Err(ColorError::Syntax(format!(
+                "`{s}` is not an ARGB color (AARRGGBB)"
+            )));
+        }
+        let alpha = u8::from_str_radix(&s[..2], 16)
+            .map_err(|_| ColorError::Syntax(format!("`{s}`: invalid alpha digits")))?;
+        let color =
+            Color::from_hex(&s[2..]).map_err(|e| ColorError::Syntax(format!("`{s}`: {e}")))?;
+        Ok((color, alpha))
+    }
+
+    // SpreadsheetML solid cell fill, as found in `styles.xml`
+    pub fn to_excel_fill_xml_fragment(&self) -> String {
+        format!(
+            "<patternFill patternType=\"solid\"><fgColor rgb=\"{}\"/></patternFill>",
+            self.to_excel_argb_hex(0xFF)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a SpreadsheetML fragment, read from the `rgb`
+    // attribute of its first `<fgColor>`. Returns the color and alpha.
+    pub fn from_excel_fill_xml_fragment(xml: &str) -> Result<(Color, u8), ColorError> {
+        Color::from_excel_argb_hex(&first_element_attr(xml, "fgColor", "rgb")?)
+    }
+
+    #[allow(dead_code)]
+    // DrawingML color element: `<{ns}:srgbClr val="RRGGBB"/>`. An empty
+    // prefix gives an unprefixed element.
+    pub fn to_ooxml_srgbclr(&self, namespace_prefix: &str) -> String {
+        let hex = self.to_hex();
+        let digits = hex.trim_start_matches('#');
+        if namespace_prefix.is_empty() {
+            format!("<srgbClr val=\"{digits}\"/>")
+        } else {
+            format!("<{namespace_prefix}:srgbClr val=\"{digits}\"/>")
+        }
+    }
+
+    #[allow(dead_code)]
+    // Constructor from the first DrawingML `srgbClr` or `sysClr` element in
+    // an XML fragment. A system color uses its `lastClr` attribute if it
+    // has one, otherwise the Windows default for its `val`. Child
+    // transforms such as `<a:lumMod>` are ignored.
+    pub fn from_ooxml_srgbclr(xml: &str) -> Result<Color, ColorError> {
+        let tokens = xml::tokenize(xml).map_err(ColorError::Syntax)?;
+        let (name, element) = tokens
+            .iter()
+            .find_map(|token| match token {
+                Token::Open { name, .. }
+                    if matches!(name.rsplit(':').next(), Some("srgbClr" | "sysClr")) =>
+                {
+                    Some((name, token))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| ColorError::Syntax("no <srgbClr> or <sysClr> element".to_string()))?;
+        let val = element
+            .attr("val")
+            .ok_or_else(|| ColorError::Syntax(format!("<{name}> has no `val` attribute")))?;
+        let hex = if name.ends_with("srgbClr") {
+            val
+        } else if let Some(last) = element.attr("lastClr") {
+            last
+        } else {
+            OOXML_SYSTEM_COLORS
+                .iter()
+                .find(|(system, _)| *system == val)
+                .map(|(_, hex)| *hex)
+                .ok_or_else(|| ColorError::Syntax(format!("unknown system color `{val}`")))?
+        };
+        Color::from_hex(hex).map_err(|e| ColorError::Syntax(format!("`{hex}`: {e}")))
+    }
+
+    // PDF operator setting the non-stroking (fill) color: `R G B rg`
+    pub fn to_pdf_devicergb(&self) -> String {
+        format!(
+            "{:.6} {:.6} {:.6} rg",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0
+        )
+    }
+
+    // PDF operator setting the stroking color: `R G B RG`
+    pub fn to_pdf_devicergb_stroking(&self) -> String {
+        let fill = self.to_pdf_devicergb();
+        format!("{}RG", fill.trim_end_matches("rg"))
+    }
+
+    // PDF operator setting a DeviceCMYK fill: `C M Y K k`, using the
+    // naive conversion of `to_cmyk`
+    pub fn to_pdf_devicecmyk(&self) -> String {
+        let (c, m, y, k) = self.to_cmyk();
+        format!("{c:.6} {m:.6} {y:.6} {k:.6} k")
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a PDF `R G B rg` or `R G B RG` operation
+    pub fn from_pdf_devicergb(s: &str) -> Result<Color, ColorError> {
+        let channels = postfix_channels(s, &["rg", "RG"], 3)?;
+        Ok(Color::from_rgb(channels[0], channels[1], channels[2]))
+    }
+
+    // PostScript operator setting the current color: `R G B setrgbcolor`
+    pub fn to_postscript_rgb(&self) -> String {
+        format!(
+            "{:.6} {:.6} {:.6} setrgbcolor",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0
+        )
+    }
+
+    // PostScript `H S B sethsbcolor`, where the hue is also scaled to [0, 1]
+    pub fn to_postscript_hsb(&self) -> String {
+        let (h, s, b) = self.to_hsb();
+        format!("{:.6} {s:.6} {b:.6} sethsbcolor", h / 360.0)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a PostScript `R G B setrgbcolor` operation
+    pub fn from_postscript_rgb(s: &str) -> Result<Color, ColorError> {
+        let channels = postfix_channels(s, &["setrgbcolor"], 3)?;
+        Ok(Color::from_rgb(channels[0], channels[1], channels[2]))
+    }
+
+    #[allow(dead_code)]
+    // Windows GDI `COLORREF`: 0x00BBGGRR, red in the lowest byte
+    pub fn to_colorref(&self) -> u32 {
+        u32::from(self.b) << 16 | u32::from(self.g) << 8 | u32::from(self.r)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a `COLORREF`; the high byte is ignored
+    pub fn from_colorref(v: u32) -> Color {
+        let [r, g, b, _] = v.to_le_bytes();
+        Color::from_rgb(r, g, b)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a registry `REG_DWORD` color, laid out like a
+    // `COLORREF` (red in bits 0-7, blue in bits 16-23, top byte unused)
+    pub fn from_registry_dword(value: u32) -> Color {
+        Color::from_colorref(value)
+    }
+
+    #[allow(dead_code)]
+    // Registry `REG_DWORD` form of the color, see `from_registry_dword`
+    pub fn to_registry_dword(&self) -> u32 {
+        self.to_colorref()
+    }
+
+    #[allow(dead_code)]
+    // The Win32 `RGB(r, g, b)` macro call producing this color's `COLORREF`
+    pub fn to_win32_rgb_macro(&self) -> String {
+        format!("RGB({}, {}, {})", self.r, self.g, self.b)
+    }
+
+    // Hex digits for Godot 4's `Color.html()`, without the `#`
+    pub fn to_godot4_html_hex(&self) -> String {
+        self.to_hex().trim_start_matches('#').to_string()
+    }
+
+    // GDScript `Color(r, g, b, a)` constructor with float components
+    pub fn to_godot4_constructor(&self, alpha: f32) -> String {
+        format!(
+            "Color({:.4}, {:.4}, {:.4}, {:.4})",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0,
+            alpha.clamp(0.0, 1.0)
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a `Color.html()` argument; the `#` is optional there
+    pub fn from_godot4_html_hex(s: &str) -> Result<Color, ColorError> {
+        Color::from_hex(s.trim())
+    }
+
+    // xcolor `\definecolor{name}{rgb}{r,g,b}` command, as used by TikZ
+    pub fn to_tikz_definecolor(&self, name: &str) -> String {
+        format!(
+            "\\definecolor{{{name}}}{{rgb}}{{{:.4},{:.4},{:.4}}}",
+            f32::from(self.r) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.b) / 255.0
+        )
+    }
+
+    // A TikZ rectangle filled with the color `name`, to be defined first
+    // with `to_tikz_definecolor`
+    pub fn to_tikz_fill(&self, name: &str) -> String {
+        format!("\\fill[{name}] (0,0) rectangle (1,1);")
+    }
+
+    #[allow(dead_code)]
+    // Constructor from an xcolor `\definecolor`, returning the color's name
+    // and value. The `rgb`, `RGB`, `HTML` and `gray` models are understood.
+    pub fn from_tikz_definecolor(cmd: &str) -> Result<(String, Color), ColorError> {
+        let invalid = || ColorError::Syntax(format!("`{cmd}` is not a \\definecolor command"));
+        let args = cmd
+            .trim()
+            .trim_end_matches(';')
+            .strip_prefix("\\definecolor")
+            .and_then(brace_groups)
+            .ok_or_else(invalid)?;
+        let [name, model, spec] = args[..] else {
+            return Err(invalid());
+        };
+        let values: Vec<&str> = spec.split(',').map(str::trim).collect();
+        let names = ["red", "green", "blue"];
+        let color = match model {
+            "rgb" if values.len() == 3 => {
+                let channels = values
+                    .iter()
+                    .zip(names)
+                    .map(|(v, name)| unit_channel(name, v.parse().map_err(|_| invalid())?))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Color::from_rgb(channels[0], channels[1], channels[2])
+            }
+            "RGB" if values.len() == 3 => {
+                let channels = values
+                    .iter()
+                    .map(|v| v.parse::<u8>().map_err(|_| invalid()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Color::from_rgb(channels[0], channels[1], channels[2])
+            }
+            "HTML" => Color::from_hex(spec)?,
+            "gray" if values.len() == 1 => {
+                let level = unit_channel("gray", values[0].parse().map_err(|_| invalid())?)?;
+                Color::from_grayscale(level)
+            }
+            "rgb" | "RGB" | "gray" => return Err(invalid()),
+            other => return Err(ColorError::UnsupportedColorSpace(other.to_string())),
+        };
+        Ok((name.to_string(), color))
+    }
+
+    // Highcharts color option: `rgba(r,g,b,a)` with an alpha, else hex
+    pub fn to_highcharts(&self, alpha: Option<f32>) -> String {
+        match alpha {
+            Some(alpha) => format!(
+                "rgba({},{},{},{})",
+                self.r,
+                self.g,
+                self.b,
+                alpha.clamp(0.0, 1.0)
+            ),
+            None => self.to_hex(),
+        }
+    }
+
+    // `theme_color` and `background_color` members of a web app manifest,
+    // both set to this color; ready to paste between other members
+    pub fn to_pwa_manifest_json_fragment(&self) -> String {
+        self.to_pwa_manifest_json_fragment_with_background(self)
+    }
+
+    // Like `to_pwa_manifest_json_fragment` with a separate background color
+    pub fn to_pwa_manifest_json_fragment_with_background(&self, background: &Color) -> String {
+        format!(
+            "\"theme_color\": {},\n\"background_color\": {}",
+            json::quote(&self.to_hex()),
+            json::quote(&background.to_hex())
+        )
+    }
+
+    // The `<meta>` tags Chrome and legacy Edge read the browser UI color from
+    pub fn to_pwa_meta_tags(&self) -> String {
+        let hex = self.to_hex();
+        format!(
+            "<meta name=\"theme-color\" content=\"{hex}\">\n\
+             <meta name=\"msapplication-navbutton-color\" content=\"{hex}\">"
+        )
+    }
+
+    #[allow(dead_code)]
+    // Sketch color object, components and alpha in [0, 1]
+    pub fn to_sketch_json(&self, alpha: f32) -> String {
+        format!(
+            "{{\"_class\": \"color\", \"alpha\": {}, \"blue\": {}, \"green\": {}, \"red\": {}}}",
+            alpha.clamp(0.0, 1.0),
+            f32::from(self.b) / 255.0,
+            f32::from(self.g) / 255.0,
+            f32::from(self.r) / 255.0
+        )
+    }
+
+    #[allow(dead_code)]
+    // Constructor from a Sketch color object, returning the color and
+    // alpha. A `colorSpace` of 1 marks Display P3 components, which are
+    // converted to sRGB and clipped; 0 or no `colorSpace` means sRGB.
+    pub fn from_sketch_json(json: &str) -> Result<(Color, f32), ColorError> {
+        let document = json::parse(json).map_err(ColorError::Syntax)?;
+        let component = |name: &'static str| -> Result<f64, ColorError> {
+            let value = document
+                .get(name)
+                .and_then(json::Json::as_f64)
+                .ok_or_else(|| ColorError::Syntax(format!("missing numeric `{name}`")))?;
+            if (0.0..=1.0).contains(&value) {
+                Ok(value)
+            } else {
+                Err(ColorError::OutOfRange {
+                    component: name,
+                    value: value as f32,
+                })
+            }
+        };
+        let rgb = [component("red")?, component("green")?, component("blue")?];
+        let alpha = match document.get("alpha") {
+            Some(_) => component("alpha")? as f32,
+            None => 1.0,
+        };
+        let color_space = match document.get("colorSpace") {
+            Some(space) => space.as_f64(),
+            None => Some(0.0),
+        };
+        let color = match color_space {
+            Some(0.0) => Color::from_rgb(
+                (rgb[0] * 255.0).round() as u8,
+                (rgb[1] * 255.0).round() as u8,
+                (rgb[2] * 255.0).round() as u8,
+            ),
+            Some(1.0) => {
+                Color::from_linear_rgb(space::display_p3_to_linear_srgb(rgb.map(|c| c as f32)))
+            }
+            Some(other) => {
+                return Err(ColorError::UnsupportedColorSpace(format!(
+                    "colorSpace {other}"
+                )))
+            }
+            None => {
+                return Err(ColorError::Syntax(
+                    "`colorSpace` must be a number".to_string(),
+                ))
+            }
+        };
+        Ok((color, alpha))
+    }
+
+    #[allow(dead_code)]
+    // JavaScript object literal `{r: R, g: G, b: B}`
+    pub fn to_js_rgb_object(&self) -> String {
+        format!("{{r: {}, g: {}, b: {}}}", self.r, self.g, self.b)
+    }
+
+    // JavaScript object literal with `hex`, `rgb`, `hsl` and `hsv`
+    // properties. Hues are in degrees and the other HSL/HSV components in
+    // percent, rounded to one decimal place.
+    pub fn to_js_full_object(&self) -> String {
+        let round = |v: f32| (v * 10.0).round() / 10.0 + 0.0;
+        let (h, s, l) = self.to_hsl();
+        let (hv, sv, v) = self.to_hsv();
+        format!(
+            "{{hex: '{}', rgb: [{}, {}, {}], hsl: {{h: {}, s: {}, l: {}}}, hsv: {{h: {}, s: {}, v: {}}}}}",
+            self.to_hex(),
+            self.r,
+            self.g,
+            self.b,
+            round(h),
+            round(s * 100.0),
+            round(l * 100.0),
+            round(hv),
+            round(sv * 100.0),
+            round(v * 100.0)
+        )
+    }
+
+    // Constructor from a color value in a YAML config such as Alacritty's:
+    // `#RRGGBB`, `0xRRGGBB` or a CSS color name, optionally in single or
+    // double quotes
+    pub fn from_yaml_color_string(s: &str) -> Result<Color, ColorError> {
+        let trimmed = s.trim();
+        let unquoted = ['\'', '"']
+            .iter()
+            .find_map(|&q| trimmed.strip_prefix(q)?.strip_suffix(q))
+            .unwrap_or(trimmed)
+            .trim();
+        if let Some(hex) = unquoted
+            .strip_prefix("0x")
+            .or_else(|| unquoted.strip_prefix("0X"))
+        {
+            return Color::from_hex(hex);
+        }
+        if unquoted.starts_with('#') {
+            return Color::from_hex(unquoted);
+        }
+        css::named_color(unquoted)
+            .ok_or_else(|| ColorError::Syntax(format!("`{s}` is not a hex code or color name")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::web_safe_palette;
+
+    // Whether `s` is `Color::NAME(A, B, C)` with each argument matching
+    // `arg`, the same check as the regex `^Color::NAME\((ARG, ){2}ARG\)$`
+    fn is_bevy_call(s: &str, name: &str, arg: impl Fn(&str) -> bool) -> bool {
+        let Some(args) = s
+            .strip_prefix("Color::")
+            .and_then(|s| s.strip_prefix(name))
+            .and_then(|s| s.strip_prefix('('))
+            .and_then(|s| s.strip_suffix(')'))
+        else {
+            return false;
+        };
+        let args: Vec<&str> = args.split(", ").collect();
+        args.len() == 3 && args.iter().all(|a| arg(a))
+    }
+
+    // `[01]\.\d{6}`
+    fn is_six_decimal_unit(s: &str) -> bool {
+        let bytes = s.as_bytes();
+        bytes.len() == 8
+            && matches!(bytes[0], b'0' | b'1')
+            && bytes[1] == b'.'
+            && bytes[2..].iter().all(u8::is_ascii_digit)
+    }
+
+    #[test]
+    fn bevy_strings_are_rust_constructor_calls() {
+        for color in web_safe_palette() {
+            let linear = color.to_bevy_linear_rgb_string();
+            assert!(
+                is_bevy_call(&linear, "rgb_linear", is_six_decimal_unit),
+                "{linear}"
+            );
+            let srgb = color.to_bevy_srgb_string();
+            let is_byte = |a: &str| a.parse::<u8>().is_ok_and(|v| v.to_string() == a);
+            assert!(is_bevy_call(&srgb, "rgb_u8", is_byte), "{srgb}");
+        }
+        assert_eq!(
+            Color::from_rgb(255, 128, 0).to_bevy_linear_rgb_string(),
+            "Color::rgb_linear(1.000000, 0.215861, 0.000000)"
+        );
+    }
+
+    #[test]
+    fn bevy_strings_round_trip() {
+        for color in web_safe_palette() {
+            for s in [
+                color.to_bevy_linear_rgb_string(),
+                color.to_bevy_srgb_string(),
+            ] {
+                assert_eq!(Color::from_bevy_rgb_string(&s), Ok(color.clone()), "{s}");
+            }
+        }
+        // Bevy's plain `rgb` constructor takes sRGB floats
+        assert_eq!(
+            Color::from_bevy_rgb_string("Color::rgb(1.0, 0.5, 0.0)"),
+            Ok(Color::from_rgb(255, 128, 0))
+        );
+        assert_eq!(
+            Color::from_bevy_rgb_string("Color::rgba_u8(1, 2, 3, 4)"),
+            Ok(Color::from_rgb(1, 2, 3))
+        );
+        assert!(Color::from_bevy_rgb_string("Color::hsl(1.0, 0.5, 0.0)").is_err());
+        assert!(Color::from_bevy_rgb_string("Color::rgb(1.5, 0.5, 0.0)").is_err());
+    }
+
+    #[test]
+    fn lottie_values_parse() {
+        assert_eq!(
+            Color::from_lottie_color("[1.0, 0.0, 0.0, 1.0]"),
+            Ok((Color::from_rgb(255, 0, 0), 1.0))
+        );
+        assert_eq!(
+            Color::from_lottie_color("[0.5, 0.5, 0.5, 0.5]"),
+            Ok((Color::from_rgb(128, 128, 128), 0.5))
+        );
+        // Alpha is optional
+        assert_eq!(
+            Color::from_lottie_color("[0, 0, 1]"),
+            Ok((Color::from_rgb(0, 0, 255), 1.0))
+        );
+        for bad in [
+            "[1.0, 0.0]",
+            "[1.0, 0.0, 0.0, 1.0, 1.0]",
+            "[1.5, 0, 0]",
+            "[0, 0, 0, 2]",
+            "red",
+        ] {
+            assert!(Color::from_lottie_color(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn lottie_output_is_a_bracketed_array() {
+        let lottie = Color::from_rgb(255, 0, 0).to_lottie_color(1.0);
+        assert_eq!(lottie, "[1.0000, 0.0000, 0.0000, 1.0000]");
+        assert!(lottie.starts_with('[') && lottie.ends_with(']'));
+        for color in web_safe_palette() {
+            let lottie = color.to_lottie_color(0.25);
+            assert_eq!(Color::from_lottie_color(&lottie), Ok((color, 0.25)));
+        }
+    }
+
+    #[test]
+    fn r_hex_round_trips_with_full_alpha() {
+        for color in web_safe_palette() {
+            let hex = color.to_r_hex(1.0);
+            assert!(hex.ends_with("FF"), "{hex}");
+            assert_eq!(Color::from_r_hex(&hex), Ok((color, 1.0)));
+        }
+    }
+
+    #[test]
+    fn r_hex_parses_partial_alpha() {
+        let (color, alpha) = Color::from_r_hex("#FF000080").unwrap();
+        assert_eq!(color, Color::from_rgb(255, 0, 0));
+        assert!((alpha - 0.502).abs() < 0.001, "{alpha}");
+        assert_eq!(
+            Color::from_r_hex("00FF00"),
+            Ok((Color::from_rgb(0, 255, 0), 1.0))
+        );
+        for bad in ["#FF00", "#FF0000F", "#FF0000GG", "#FF00é0"] {
+            assert!(Color::from_r_hex(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn r_rgb_call_only_passes_alpha_when_translucent() {
+        let red = Color::from_rgb(255, 0, 0);
+        assert_eq!(red.to_r_rgb_call(1.0), "rgb(1.000, 0.000, 0.000)");
+        assert_eq!(red.to_r_rgb_call(0.5), "rgb(1.000, 0.000, 0.000, 0.500)");
+        assert_eq!(red.to_r_hex(0.5), "#FF000080");
+    }
+
+    #[test]
+    fn nscolor_swift_and_objc_parse_to_red() {
+        let red = Ok((Color::from_rgb(255, 0, 0), 1.0));
+        assert_eq!(
+            Color::from_nscolor_swift("NSColor(red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0)"),
+            red
+        );
+        assert_eq!(
+            Color::from_nscolor_swift("NSColor(srgbRed: 1, green: 0, blue: 0, alpha: 1)"),
+            red
+        );
+        assert_eq!(
+            Color::from_nscolor_objc("[NSColor colorWithRed:1.0 green:0.0 blue:0.0 alpha:1.0]"),
+            red
+        );
+        assert_eq!(
+            Color::from_nscolor_objc(
+                "[NSColor colorWithDeviceRed:1.0f green:0.0f blue:0.0f alpha:1.0f]"
+            ),
+            red
+        );
+    }
+
+    #[test]
+    fn nscolor_forms_round_trip() {
+        for color in web_safe_palette() {
+            let swift = color.to_nscolor_swift(0.5);
+            assert_eq!(
+                Color::from_nscolor_swift(&swift),
+                Ok((color.clone(), 0.5)),
+                "{swift}"
+            );
+            let objc = color.to_nscolor_objc(0.5);
+            assert_eq!(Color::from_nscolor_objc(&objc), Ok((color, 0.5)), "{objc}");
+        }
+    }
+
+    #[test]
+    fn nscolor_rejects_wrong_labels_and_ranges() {
+        for bad in [
+            "NSColor(red: 1.0, blue: 0.0, green: 0.0, alpha: 1.0)",
+            "NSColor(red: 1.0, green: 0.0, blue: 0.0)",
+            "NSColor(red: 1.5, green: 0.0, blue: 0.0, alpha: 1.0)",
+            "UIColor(red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0)",
+        ] {
+            assert!(Color::from_nscolor_swift(bad).is_err(), "{bad}");
+        }
+        for bad in [
+            "[NSColor colorWithHue:1.0 green:0.0 blue:0.0 alpha:1.0]",
+            "[NSColor colorWithRed:1.0 green:0.0 blue:0.0 alpha:2.0]",
+            "NSColor colorWithRed:1.0 green:0.0 blue:0.0 alpha:1.0",
+        ] {
+            assert!(Color::from_nscolor_objc(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn xcode_contents_json_follows_the_colorset_layout() {
+        let json = Color::from_rgb(255, 128, 0).to_xcode_color_json(0.5, DisplayGamut::Srgb);
+        let document = json::parse(&json).unwrap();
+        let info = document.get("info").unwrap();
+        assert_eq!(
+            info.get("author").and_then(json::Json::as_str),
+            Some("xcode")
+        );
+        assert_eq!(info.get("version").and_then(json::Json::as_f64), Some(1.0));
+
+        let colors = document
+            .get("colors")
+            .and_then(json::Json::as_array)
+            .unwrap();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(
+            colors[0].get("idiom").and_then(json::Json::as_str),
+            Some("universal")
+        );
+        let color = colors[0].get("color").unwrap();
+        assert_eq!(
+            color.get("color-space").and_then(json::Json::as_str),
+            Some("srgb")
+        );
+        let components = color.get("components").unwrap();
+        // Xcode writes components as strings, not JSON numbers
+        for (name, expected) in [
+            ("red", "1.0000"),
+            ("green", "0.5020"),
+            ("blue", "0.0000"),
+            ("alpha", "0.5000"),
+        ] {
+            assert_eq!(
+                components.get(name).and_then(json::Json::as_str),
+                Some(expected),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn xcode_contents_json_round_trips_both_gamuts() {
+        for color in web_safe_palette() {
+            for gamut in [DisplayGamut::Srgb, DisplayGamut::DisplayP3] {
+                let json = color.to_xcode_color_json(1.0, gamut);
+                assert_eq!(
+                    parse_xcode_color_json(&json),
+                    Ok((color.clone(), 1.0, gamut)),
+                    "{json}"
+                );
+            }
+        }
+        let p3 = Color::from_rgb(255, 0, 0).to_xcode_color_json(1.0, DisplayGamut::DisplayP3);
+        assert!(p3.contains(r#""color-space" : "display-p3""#));
+    }
+
+    #[test]
+    fn xcode_parser_accepts_integer_and_hex_components() {
+        let json = r#"{"colors": [{"idiom": "universal", "color": {
+            "color-space": "srgb",
+            "components": {"red": "255", "green": "0x80", "blue": 0, "alpha": "1.000"}
+        }}]}"#;
+        assert_eq!(
+            parse_xcode_color_json(json),
+            Ok((Color::from_rgb(255, 128, 0), 1.0, DisplayGamut::Srgb))
+        );
+        assert!(parse_xcode_color_json(r#"{"colors": []}"#).is_err());
+        assert!(parse_xcode_color_json(
+            r#"{"colors": [{"color": {"color-space": "gray-gamma-22", "components": {}}}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sdl2_mapped_pixels_follow_each_format_byte_order() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(
+            Color::from_sdl2_mapped(0x0012_3456, PixelFormatKind::Rgb888),
+            color
+        );
+        // The unused top byte of RGB888 is ignored
+        assert_eq!(
+            Color::from_sdl2_mapped(0xFF12_3456, PixelFormatKind::Rgb888),
+            color
+        );
+        assert_eq!(
+            Color::from_sdl2_mapped(0x8012_3456, PixelFormatKind::Argb8888),
+            color
+        );
+        assert_eq!(
+            Color::from_sdl2_mapped(0x1234_5680, PixelFormatKind::Rgba8888),
+            color
+        );
+        assert_eq!(
+            Color::from_sdl2_mapped(0xFF00_0000, PixelFormatKind::Rgba8888),
+            Color::from_rgb(255, 0, 0)
+        );
+        assert_eq!(
+            Color::from_sdl2_mapped(0xFF00_0000, PixelFormatKind::Argb8888),
+            Color::from_rgb(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn sdl2_tuples_round_trip() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(color.to_sdl2_tuple(0x80), (0x12, 0x34, 0x56, 0x80));
+        assert_eq!(
+            Color::from_sdl2_tuple(0x12, 0x34, 0x56, 0x80),
+            (color, 0x80)
+        );
+    }
+
+    #[test]
+    fn excel_argb_puts_alpha_first() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(color.to_excel_argb_hex(0xFF), "FF123456");
+        assert_eq!(color.to_excel_argb_hex(0x80), "80123456");
+        assert_eq!(Color::from_excel_argb_hex("80123456"), Ok((color, 0x80)));
+        for bad in ["123456", "GG123456", "FF12345G", "FF1234567"] {
+            assert!(Color::from_excel_argb_hex(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn excel_fill_fragment_round_trips() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(
+            color.to_excel_fill_xml_fragment(),
+            r#"<patternFill patternType="solid"><fgColor rgb="FF123456"/></patternFill>"#
+        );
+        for color in web_safe_palette() {
+            let xml = color.to_excel_fill_xml_fragment();
+            assert_eq!(Color::from_excel_fill_xml_fragment(&xml), Ok((color, 0xFF)));
+        }
+        assert!(Color::from_excel_fill_xml_fragment("<patternFill/>").is_err());
+    }
+
+    #[test]
+    fn ooxml_srgbclr_parses_the_val_attribute() {
+        assert_eq!(
+            Color::from_ooxml_srgbclr(r#"<a:srgbClr val="003366"/>"#),
+            Ok(Color::from_rgb(0, 51, 102))
+        );
+        assert_eq!(
+            Color::from_ooxml_srgbclr(
+                r#"<a:solidFill><a:srgbClr val="FF0000"><a:lumMod val="75000"/></a:srgbClr></a:solidFill>"#
+            ),
+            Ok(Color::from_rgb(255, 0, 0))
+        );
+        assert!(Color::from_ooxml_srgbclr(r#"<a:srgbClr val="0033"/>"#).is_err());
+        assert!(Color::from_ooxml_srgbclr(r#"<a:srgbClr/>"#).is_err());
+        assert!(Color::from_ooxml_srgbclr(r#"<a:schemeClr val="accent1"/>"#).is_err());
+    }
+
+    #[test]
+    fn ooxml_sysclr_uses_last_color_or_the_default() {
+        assert_eq!(
+            Color::from_ooxml_srgbclr(r#"<a:sysClr val="window" lastClr="FEFEFE"/>"#),
+            Ok(Color::from_rgb(254, 254, 254))
+        );
+        assert_eq!(
+            Color::from_ooxml_srgbclr(r#"<a:sysClr val="windowText"/>"#),
+            Ok(Color::from_rgb(0, 0, 0))
+        );
+        assert!(Color::from_ooxml_srgbclr(r#"<a:sysClr val="plaid"/>"#).is_err());
+    }
+
+    #[test]
+    fn ooxml_srgbclr_round_trips() {
+        let color = Color::from_rgb(0, 51, 102);
+        assert_eq!(color.to_ooxml_srgbclr("a"), r#"<a:srgbClr val="003366"/>"#);
+        assert_eq!(color.to_ooxml_srgbclr(""), r#"<srgbClr val="003366"/>"#);
+        for color in web_safe_palette() {
+            assert_eq!(
+                Color::from_ooxml_srgbclr(&color.to_ooxml_srgbclr("a")),
+                Ok(color)
+            );
+        }
+    }
+
+    #[test]
+    fn pdf_operators_write_six_decimal_channels() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        assert_eq!(red.to_pdf_devicergb(), "1.000000 0.000000 0.000000 rg");
+        assert_eq!(blue.to_pdf_devicergb(), "0.000000 0.000000 1.000000 rg");
+        assert_eq!(
+            blue.to_pdf_devicergb_stroking(),
+            "0.000000 0.000000 1.000000 RG"
+        );
+        assert_eq!(
+            Color::from_rgb(255, 0, 0).to_pdf_devicecmyk(),
+            "0.000000 1.000000 1.000000 0.000000 k"
+        );
+    }
+
+    #[test]
+    fn pdf_operators_round_trip() {
+        for color in web_safe_palette() {
+            assert_eq!(
+                Color::from_pdf_devicergb(&color.to_pdf_devicergb()),
+                Ok(color.clone())
+            );
+            assert_eq!(
+                Color::from_pdf_devicergb(&color.to_pdf_devicergb_stroking()),
+                Ok(color)
+            );
+        }
+        for bad in [
+            "1 0 0",
+            "1 0 rg",
+            "1 0 0 0 rg",
+            "1 0 0 k",
+            "2 0 0 rg",
+            "x 0 0 rg",
+        ] {
+            assert!(Color::from_pdf_devicergb(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn postscript_rgb_writes_channel_ratios() {
+        for color in web_safe_palette() {
+            let ps = color.to_postscript_rgb();
+            let words: Vec<&str> = ps.split_whitespace().collect();
+            assert_eq!(words.len(), 4, "{ps}");
+            assert_eq!(words[3], "setrgbcolor");
+            for (word, channel) in words.iter().zip([color.r, color.g, color.b]) {
+                assert_eq!(*word, format!("{:.6}", f32::from(channel) / 255.0));
+            }
+        }
+        assert_eq!(
+            Color::from_rgb(0, 51, 102).to_postscript_rgb(),
+            "0.000000 0.200000 0.400000 setrgbcolor"
+        );
+        assert_eq!(
+            Color::from_rgb(0, 0, 255).to_postscript_hsb(),
+            "0.666667 1.000000 1.000000 sethsbcolor"
+        );
+    }
+
+    #[test]
+    fn postscript_rgb_parses_primaries_exactly() {
+        for primary in [
+            Color::from_rgb(255, 0, 0),
+            Color::from_rgb(0, 255, 0),
+            Color::from_rgb(0, 0, 255),
+            Color::from_rgb(0, 0, 0),
+            Color::from_rgb(255, 255, 255),
+        ] {
+            let ps = primary.to_postscript_rgb();
+            assert_eq!(Color::from_postscript_rgb(&ps), Ok(primary));
+        }
+        assert_eq!(
+            Color::from_postscript_rgb("1 0 0 setrgbcolor"),
+            Ok(Color::from_rgb(255, 0, 0))
+        );
+        assert!(Color::from_postscript_rgb("1 0 0 rg").is_err());
+        assert!(Color::from_postscript_rgb("1 0 setrgbcolor").is_err());
+    }
+
+    #[test]
+    fn colorref_keeps_red_in_the_lowest_byte() {
+        let red = Color::from_rgb(255, 0, 0);
+        assert_eq!(red.to_colorref(), 0x0000_00FF);
+        assert_eq!(Color::from_rgb(0, 0, 255).to_colorref(), 0x00FF_0000);
+        assert_eq!(Color::from_rgb(0x12, 0x34, 0x56).to_colorref(), 0x0056_3412);
+        assert_eq!(red.to_win32_rgb_macro(), "RGB(255, 0, 0)");
+        assert_eq!(
+            Color::from_rgb(0x12, 0x34, 0x56).to_win32_rgb_macro(),
+            "RGB(18, 52, 86)"
+        );
+    }
+
+    #[test]
+    fn colorref_round_trips_and_ignores_the_high_byte() {
+        for color in web_safe_palette() {
+            assert_eq!(Color::from_colorref(color.to_colorref()), color);
+        }
+        assert_eq!(
+            Color::from_colorref(0xFF56_3412),
+            Color::from_rgb(0x12, 0x34, 0x56)
+        );
+    }
+
+    #[test]
+    fn godot4_html_hex_round_trips() {
+        assert_eq!(Color::from_rgb(255, 0, 0).to_godot4_html_hex(), "FF0000");
+        for color in web_safe_palette() {
+            let hex = color.to_godot4_html_hex();
+            assert!(!hex.starts_with('#'));
+            assert_eq!(Color::from_godot4_html_hex(&hex), Ok(color));
+        }
+        assert_eq!(
+            Color::from_godot4_html_hex("#00ff00"),
+            Ok(Color::from_rgb(0, 255, 0))
+        );
+        assert!(Color::from_godot4_html_hex("FF00").is_err());
+    }
+
+    #[test]
+    fn godot4_constructor_is_a_gdscript_call() {
+        assert_eq!(
+            Color::from_rgb(255, 128, 0).to_godot4_constructor(0.5),
+            "Color(1.0000, 0.5020, 0.0000, 0.5000)"
+        );
+        for color in web_safe_palette() {
+            let call = color.to_godot4_constructor(2.0);
+            let args: Vec<f32> = call
+                .strip_prefix("Color(")
+                .and_then(|s| s.strip_suffix(')'))
+                .unwrap_or_else(|| panic!("{call}"))
+                .split(", ")
+                .map(|a| a.parse().unwrap())
+                .collect();
+            assert_eq!(args.len(), 4, "{call}");
+            assert!(args.iter().all(|a| (0.0..=1.0).contains(a)), "{call}");
+            assert_eq!(args[3], 1.0);
+        }
+    }
+
+    #[test]
+    fn registry_dword_is_bgr_ordered() {
+        assert_eq!(
+            Color::from_registry_dword(0x0000FF),
+            Color::from_rgb(255, 0, 0)
+        );
+        assert_eq!(
+            Color::from_registry_dword(0xFF0000),
+            Color::from_rgb(0, 0, 255)
+        );
+        assert_eq!(
+            Color::from_registry_dword(0x00563412),
+            Color::from_rgb(0x12, 0x34, 0x56)
+        );
+        assert_eq!(
+            Color::from_rgb(0x12, 0x34, 0x56).to_registry_dword(),
+            0x00563412
+        );
+        assert_eq!(
+            Color::from_registry_dword(0xAA0000FF),
+            Color::from_rgb(255, 0, 0)
+        );
+    }
+
+    #[test]
+    fn registry_dword_round_trips() {
+        for color in web_safe_palette() {
+            let dword = color.to_registry_dword();
+            assert_eq!(dword >> 24, 0);
+            assert_eq!(Color::from_registry_dword(dword), color);
+        }
+    }
+
+    #[test]
+    fn tikz_definecolor_parses_name_and_rgb() {
+        let (name, color) =
+            Color::from_tikz_definecolor(r"\definecolor{brandred}{rgb}{0.8,0.1,0.1}").unwrap();
+        assert_eq!(name, "brandred");
+        let expected = Color::from_rgb(204, 25, 25);
+        for (got, want) in [
+            (color.r, expected.r),
+            (color.g, expected.g),
+            (color.b, expected.b),
+        ] {
+            assert!(got.abs_diff(want) <= 1, "{color:?}");
+        }
+    }
+
+    #[test]
+    fn tikz_definecolor_round_trips() {
+        let color = Color::from_rgb(204, 25, 25);
+        let cmd = color.to_tikz_definecolor("brandred");
+        assert_eq!(cmd, r"\definecolor{brandred}{rgb}{0.8000,0.0980,0.0980}");
+        assert_eq!(
+            Color::from_tikz_definecolor(&cmd),
+            Ok(("brandred".to_string(), color.clone()))
+        );
+        assert_eq!(
+            color.to_tikz_fill("brandred"),
+            r"\fill[brandred] (0,0) rectangle (1,1);"
+        );
+        assert!(Color::from_tikz_definecolor(r"\definecolor{x}{rgb}{0.8,0.1}").is_err());
+        assert!(matches!(
+            Color::from_tikz_definecolor(r"\definecolor{x}{cmyk}{0,1,1,0}"),
+            Err(ColorError::UnsupportedColorSpace(_))
+        ));
+    }
+
+    #[test]
+    fn highcharts_color_option_is_a_string_literal() {
+        let color = Color::from_rgb(255, 128, 0);
+        assert_eq!(color.to_highcharts(None), "#FF8000");
+        assert_eq!(color.to_highcharts(Some(0.5)), "rgba(255,128,0,0.5)");
+        assert_eq!(color.to_highcharts(Some(3.0)), "rgba(255,128,0,1)");
+        let config = format!(
+            r#"{{"color": {}}}"#,
+            json::quote(&color.to_highcharts(Some(0.5)))
+        );
+        let parsed = json::parse(&config).unwrap();
+        assert_eq!(
+            parsed.get("color").and_then(json::Json::as_str),
+            Some("rgba(255,128,0,0.5)")
+        );
+    }
+
+    #[test]
+    fn highcharts_color_axis_stops_are_valid_json() {
+        let from = Color::from_rgb(0, 0, 0);
+        let to = Color::from_rgb(255, 255, 255);
+        let stops = highcharts_color_axis(&from, &to, 3);
+        assert_eq!(
+            stops,
+            r##"[[0, "#000000"], [0.5, "#808080"], [1, "#FFFFFF"]]"##
+        );
+
+        let config = format!(
+            r#"{{"colorAxis": {{"stops": {}}}}}"#,
+            highcharts_color_axis(&from, &to, 5)
+        );
+        let parsed = json::parse(&config).unwrap();
+        let stops = parsed
+            .get("colorAxis")
+            .and_then(|axis| axis.get("stops"))
+            .and_then(json::Json::as_array)
+            .unwrap();
+        assert_eq!(stops.len(), 5);
+        let mut last = -1.0;
+        for stop in stops {
+            let stop = stop.as_array().unwrap();
+            assert_eq!(stop.len(), 2);
+            let offset = stop[0].as_f64().unwrap();
+            assert!(offset > last && (0.0..=1.0).contains(&offset));
+            last = offset;
+            assert!(Color::from_hex(stop[1].as_str().unwrap()).is_ok());
+        }
+        assert_eq!(last, 1.0);
+        // Fewer than two steps still spans both ends
+        assert_eq!(
+            highcharts_color_axis(&from, &to, 0),
+            r##"[[0, "#000000"], [1, "#FFFFFF"]]"##
+        );
+    }
+
+    #[test]
+    fn pwa_fragment_embeds_in_a_manifest() {
+        let theme = Color::from_rgb(0x33, 0x66, 0x99);
+        let background = Color::from_rgb(255, 255, 255);
+        assert_eq!(
+            theme.to_pwa_manifest_json_fragment(),
+            "\"theme_color\": \"#336699\",\n\"background_color\": \"#336699\""
+        );
+        for (fragment, expected_background) in [
+            (theme.to_pwa_manifest_json_fragment(), "#336699"),
+            (
+                theme.to_pwa_manifest_json_fragment_with_background(&background),
+                "#FFFFFF",
+            ),
+        ] {
+            let manifest = format!(
+                "{{\n\"name\": \"Chromatic\",\n{fragment},\n\"display\": \"standalone\"\n}}"
+            );
+            let parsed = json::parse(&manifest).unwrap_or_else(|e| panic!("{e}: {manifest}"));
+            let member = |key| parsed.get(key).and_then(json::Json::as_str);
+            assert_eq!(member("theme_color"), Some("#336699"));
+            assert_eq!(member("background_color"), Some(expected_background));
+            assert_eq!(member("display"), Some("standalone"));
+        }
+    }
+
+    #[test]
+    fn pwa_meta_tags_name_both_browser_colors() {
+        assert_eq!(
+            Color::from_rgb(0x33, 0x66, 0x99).to_pwa_meta_tags(),
+            "<meta name=\"theme-color\" content=\"#336699\">\n\
+             <meta name=\"msapplication-navbutton-color\" content=\"#336699\">"
+        );
+    }
+
+    // A fill color as it appears in a Sketch document's JSON
+    const SKETCH_COLOR: &str = r#"{
+        "_class": "color",
+        "alpha": 0.8,
+        "blue": 0.2,
+        "green": 0.4,
+        "red": 1
+    }"#;
+
+    #[test]
+    fn sketch_fixture_parses_to_rgba() {
+        let (color, alpha) = Color::from_sketch_json(SKETCH_COLOR).unwrap();
+        assert_eq!(color, Color::from_rgb(255, 102, 51));
+        assert!((alpha - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sketch_color_space_selects_srgb_or_display_p3() {
+        let srgb = r#"{"_class": "color", "colorSpace": 0, "red": 1, "green": 0, "blue": 0}"#;
+        assert_eq!(
+            Color::from_sketch_json(srgb),
+            Ok((Color::from_rgb(255, 0, 0), 1.0))
+        );
+        // Display P3 white is sRGB white; P3 red lies outside sRGB and clips
+        let p3_white = r#"{"colorSpace": 1, "red": 1, "green": 1, "blue": 1, "alpha": 1}"#;
+        assert_eq!(
+            Color::from_sketch_json(p3_white),
+            Ok((Color::from_rgb(255, 255, 255), 1.0))
+        );
+        let p3_red = r#"{"colorSpace": 1, "red": 1, "green": 0, "blue": 0}"#;
+        let (red, _) = Color::from_sketch_json(p3_red).unwrap();
+        assert_eq!((red.r, red.g, red.b), (255, 0, 0));
+        assert!(matches!(
+            Color::from_sketch_json(r#"{"colorSpace": 2, "red": 1, "green": 0, "blue": 0}"#),
+            Err(ColorError::UnsupportedColorSpace(_))
+        ));
+        assert!(matches!(
+            Color::from_sketch_json(r#"{"red": 1.5, "green": 0, "blue": 0}"#),
+            Err(ColorError::OutOfRange {
+                component: "red",
+                ..
+            })
+        ));
+        assert!(Color::from_sketch_json(r#"{"red": 1, "green": 0}"#).is_err());
+    }
+
+    #[test]
+    fn sketch_json_round_trips() {
+        let json = Color::from_rgb(255, 102, 51).to_sketch_json(0.8);
+        assert_eq!(
+            json::parse(&json)
+                .unwrap()
+                .get("_class")
+                .and_then(json::Json::as_str),
+            Some("color")
+        );
+        for color in web_safe_palette() {
+            let (parsed, alpha) = Color::from_sketch_json(&color.to_sketch_json(0.5)).unwrap();
+            assert_eq!(parsed, color);
+            assert_eq!(alpha, 0.5);
+        }
+    }
+
+    // Rewrite the JSON5 subset the JS object literals use (bare identifier
+    // keys and single-quoted strings without escapes) as plain JSON
+    fn json5_to_json(js: &str) -> String {
+        let mut out = String::new();
+        let mut chars = js.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    out.push('"');
+                    for c in chars.by_ref() {
+                        if c == '\'' {
+                            break;
+                        }
+                        out.push(c);
+                    }
+                    out.push('"');
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut word = c.to_string();
+                    while let Some(&next) = chars.peek() {
+                        if !(next.is_ascii_alphanumeric() || next == '_') {
+                            break;
+                        }
+                        word.push(next);
+                        chars.next();
+                    }
+                    if chars.peek() == Some(&':') {
+                        out.push_str(&json::quote(&word));
+                    } else {
+                        out.push_str(&word);
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn js_rgb_object_is_an_object_literal() {
+        let js = Color::from_rgb(255, 0, 0).to_js_rgb_object();
+        assert_eq!(js, "{r: 255, g: 0, b: 0}");
+        let object = json::parse(&json5_to_json(&js)).unwrap();
+        let channel = |key| object.get(key).and_then(json::Json::as_f64);
+        assert_eq!(
+            (channel("r"), channel("g"), channel("b")),
+            (Some(255.0), Some(0.0), Some(0.0))
+        );
+    }
+
+    #[test]
+    fn js_full_object_is_an_object_literal() {
+        let js = Color::from_rgb(255, 0, 0).to_js_full_object();
+        assert_eq!(
+            js,
+            "{hex: '#FF0000', rgb: [255, 0, 0], hsl: {h: 0, s: 100, l: 50}, hsv: {h: 0, s: 100, v: 100}}"
+        );
+        for color in web_safe_palette() {
+            let js = color.to_js_full_object();
+            let object = json::parse(&json5_to_json(&js)).unwrap_or_else(|e| panic!("{e}: {js}"));
+            assert_eq!(
+                object.get("hex").and_then(json::Json::as_str),
+                Some(color.to_hex().as_str())
+            );
+            let rgb: Vec<f64> = object
+                .get("rgb")
+                .and_then(json::Json::as_array)
+                .unwrap()
+                .iter()
+                .filter_map(json::Json::as_f64)
+                .collect();
+            assert_eq!(rgb, [color.r, color.g, color.b].map(f64::from));
+            for (space, keys) in [("hsl", ["h", "s", "l"]), ("hsv", ["h", "s", "v"])] {
+                let value = object.get(space).unwrap();
+                for key in keys {
+                    let n = value.get(key).and_then(json::Json::as_f64).unwrap();
+                    assert!((0.0..=360.0).contains(&n), "{js}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn yaml_color_forms_all_give_red() {
+        let red = Color::from_rgb(255, 0, 0);
+        for value in [
+            "'0xFF0000'",
+            "\"#FF0000\"",
+            "'red'",
+            "0xFF0000",
+            "'#ff0000'",
+            " \"0xff0000\" ",
+        ] {
+            assert_eq!(
+                Color::from_yaml_color_string(value),
+                Ok(red.clone()),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn yaml_color_rejects_unknown_values() {
+        for value in ["'notacolor'", "0xFF00", "'#GG0000'", "''", "'red"] {
+            assert!(Color::from_yaml_color_string(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,563 @@
+use crate::error::ColorParseError;
+use crate::space::{bradford_adapt, WhitePoint};
+use crate::Color;
+
+// Tone reproduction curve of one channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Identity,
+    Gamma(f32),
+    // Sampled curve, evenly spaced over [0, 1]
+    Table(Vec<f32>),
+    // ICC parametric curve: function type and its parameters
+    Parametric(u16, [f32; 7]),
+}
+
+impl Curve {
+    // Device value to linear light
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Curve::Identity => x,
+            Curve::Gamma(g) => x.powf(*g),
+            Curve::Table(table) => {
+                let pos = x * (table.len() - 1) as f32;
+                let i = (pos.floor() as usize).min(table.len() - 2);
+                let frac = pos - i as f32;
+                table[i] + (table[i + 1] - table[i]) * frac
+            }
+            Curve::Parametric(kind, p) => {
+                let [g, a, b, c, d, e, f] = *p;
+                match kind {
+                    0 => x.powf(g),
+                    1 if x >= -b / a => (a * x + b).powf(g),
+                    1 => 0.0,
+                    2 if x >= -b / a => (a * x + b).powf(g) + c,
+                    2 => c,
+                    3 if x >= d => (a * x + b).powf(g),
+                    3 => c * x,
+                    _ if x >= d => (a * x + b).powf(g) + e,
+                    _ => c * x + f,
+                }
+            }
+        }
+    }
+}
+
+// A matrix/TRC RGB display profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct IccProfile {
+    // Columns are the red, green and blue colorants in PCS XYZ
+    pub matrix: [[f32; 3]; 3],
+    pub curves: [Curve; 3],
+    // Illuminant of the profile connection space, normally D50
+    pub pcs_white: [f32; 3],
+}
+
+fn malformed(reason: impl Into<String>) -> ColorParseError {
+    ColorParseError::Malformed(reason.into())
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16, ColorParseError> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| malformed("profile is truncated"))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32, ColorParseError> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| malformed("profile is truncated"))
+}
+
+fn read_s15_fixed16(data: &[u8], at: usize) -> Result<f32, ColorParseError> {
+    Ok(read_u32(data, at)? as i32 as f32 / 65536.0)
+}
+
+fn read_xyz(data: &[u8], at: usize) -> Result<[f32; 3], ColorParseError> {
+    Ok([
+        read_s15_fixed16(data, at)?,
+        read_s15_fixed16(data, at + 4)?,
+        read_s15_fixed16(data, at + 8)?,
+    ])
+}
+
+impl IccProfile {
+    // Parse the matrix/TRC tags of an RGB profile. Profiles that only carry
+    // LUT transforms (A2B0 and friends) are reported as unsupported.
+    pub fn parse(data: &[u8]) -> Result<Self, ColorParseError> {
+        if data.len() < 132 || &data[36..40] != b"acsp" {
+            return Err(malformed("not an ICC profile (missing `acsp` signature)"));
+        }
+        if &data[16..20] != b"RGB " {
+            return Err(ColorParseError::Unsupported(format!(
+                "{} data color space, only RGB profiles are handled",
+                String::from_utf8_lossy(&data[16..20]).trim()
+            )));
+        }
+        if &data[20..24] != b"XYZ " {
+            return Err(ColorParseError::Unsupported(
+                "Lab profile connection space".to_string(),
+            ));
+        }
+        let pcs_white = read_xyz(data, 68)?;
+
+        // The count comes straight from the file, so make sure the table fits
+        // before trusting it
+        let count = read_u32(data, 128)? as usize;
+        let table_end = count
+            .checked_mul(12)
+            .and_then(|len| len.checked_add(132))
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| malformed("tag table is truncated"))?;
+        let mut tags = Vec::with_capacity((table_end - 132) / 12);
+        for i in 0..count {
+            let entry = 132 + i * 12;
+            let signature = data
+                .get(entry..entry + 4)
+                .ok_or_else(|| malformed("tag table is truncated"))?;
+            let offset = read_u32(data, entry + 4)? as usize;
+            let size = read_u32(data, entry + 8)? as usize;
+            let body = data
+                .get(offset..offset.saturating_add(size))
+                .ok_or_else(|| malformed("tag data lies outside the profile"))?;
+            tags.push((signature, body));
+        }
+        let tag = |signature: &[u8; 4]| {
+            tags.iter()
+                .find(|(s, _)| *s == signature)
+                .map(|(_, body)| *body)
+        };
+
+        let colorant = |signature: &[u8; 4]| -> Result<[f32; 3], ColorParseError> {
+            let body = tag(signature).ok_or_else(|| {
+                ColorParseError::Unsupported(
+                    "profile has no matrix/TRC tags (LUT-based profiles are not supported)"
+                        .to_string(),
+                )
+            })?;
+            if body.get(0..4) != Some(b"XYZ ") {
+                return Err(malformed("colorant tag is not of type XYZ"));
+            }
+            read_xyz(body, 8)
+        };
+        let [r, g, b] = [colorant(b"rXYZ")?, colorant(b"gXYZ")?, colorant(b"bXYZ")?];
+        let matrix = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+
+        let curve = |signature: &[u8; 4]| -> Result<Curve, ColorParseError> {
+            let body = tag(signature).ok_or_else(|| {
+                malformed(format!(
+                    "missing {} tag",
+                    String::from_utf8_lossy(signature)
+                ))
+            })?;
+            parse_curve(body)
+        };
+        let curves = [curve(b"rTRC")?, curve(b"gTRC")?, curve(b"bTRC")?];
+
+        Ok(Self {
+            matrix,
+            curves,
+            pcs_white,
+        })
+    }
+
+    // Device RGB in [0, 1] to XYZ in the profile connection space
+    pub fn to_pcs_xyz(&self, device: [f32; 3]) -> [f32; 3] {
+        let lin = [
+            self.curves[0].apply(device[0]),
+            self.curves[1].apply(device[1]),
+            self.curves[2].apply(device[2]),
+        ];
+        let m = &self.matrix;
+        [
+            m[0][0] * lin[0] + m[0][1] * lin[1] + m[0][2] * lin[2],
+            m[1][0] * lin[0] + m[1][1] * lin[1] + m[1][2] * lin[2],
+            m[2][0] * lin[0] + m[2][1] * lin[1] + m[2][2] * lin[2],
+        ]
+    }
+
+    // The PCS illuminant as a white point
+    fn pcs_white_point(&self) -> WhitePoint {
+        let [x, y, z] = self.pcs_white;
+        WhitePoint::Custom {
+            x: x / (x + y + z),
+            y: y / (x + y + z),
+        }
+    }
+
+    // Device RGB in [0, 1] to XYZ adapted from the PCS white to `white`,
+    // without clipping to any RGB gamut
+    pub fn to_xyz_with_white(&self, device: [f32; 3], white: WhitePoint) -> [f32; 3] {
+        bradford_adapt(self.to_pcs_xyz(device), self.pcs_white_point(), white)
+    }
+
+    // Device RGB in [0, 1] to sRGB, adapting from the PCS white to D65
+    pub fn to_srgb(&self, device: [f32; 3]) -> Color {
+        Color::from_xyz(self.to_xyz_with_white(device, WhitePoint::D65))
+    }
+}
+
+fn parse_curve(body: &[u8]) -> Result<Curve, ColorParseError> {
+    match body.get(0..4) {
+        Some(b"curv") => {
+            let count = read_u32(body, 8)? as usize;
+            match count {
+                0 => Ok(Curve::Identity),
+                1 => Ok(Curve::Gamma(f32::from(read_u16(body, 12)?) / 256.0)),
+                _ => {
+                    let table = (0..count)
+                        .map(|i| read_u16(body, 12 + i * 2).map(|v| f32::from(v) / 65535.0))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(Curve::Table(table))
+                }
+            }
+        }
+        Some(b"para") => {
+            let kind = read_u16(body, 8)?;
+            let count = match kind {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => {
+                    return Err(ColorParseError::Unsupported(format!(
+                        "parametric curve type {kind}"
+                    )))
+                }
+            };
+            let mut params = [0.0; 7];
+            for (i, param) in params.iter_mut().take(count).enumerate() {
+                *param = read_s15_fixed16(body, 12 + i * 4)?;
+            }
+            Ok(Curve::Parametric(kind, params))
+        }
+        _ => Err(ColorParseError::Unsupported(
+            "TRC tag is neither curv nor para".to_string(),
+        )),
+    }
+}
+
+fn s15_fixed16(value: f32) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(xyz: [f32; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for v in xyz {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+// A `mluc` tag holding one en-US string
+fn text_tag(text: &str) -> Vec<u8> {
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let mut tag = b"mluc\0\0\0\0".to_vec();
+    for field in [1, 12] {
+        tag.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    tag.extend_from_slice(b"enUS");
+    tag.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+    tag.extend_from_slice(&28u32.to_be_bytes());
+    tag.extend_from_slice(&utf16);
+    tag
+}
+
+// Build a minimal ICC v4.3 sRGB display profile: D50-adapted colorants,
+// the sRGB transfer function as a type 3 parametric curve shared by all
+// three channels, and the Bradford `chad` matrix used for the adaptation.
+pub fn srgb_icc_profile() -> Vec<u8> {
+    let d50 = WhitePoint::D50.xyz();
+    let adapt = |xyz| bradford_adapt(xyz, WhitePoint::D65, WhitePoint::D50);
+    let colorant = |c: Color| xyz_tag(adapt(c.to_xyz()));
+
+    let mut trc = b"para\0\0\0\0".to_vec();
+    trc.extend_from_slice(&3u16.to_be_bytes());
+    trc.extend_from_slice(&[0, 0]);
+    for p in [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.040_45] {
+        trc.extend_from_slice(&s15_fixed16(p));
+    }
+
+    // The adaptation is linear, so its matrix columns are the adapted
+    // unit vectors
+    let columns = [
+        adapt([1.0, 0.0, 0.0]),
+        adapt([0.0, 1.0, 0.0]),
+        adapt([0.0, 0.0, 1.0]),
+    ];
+    let mut chad = b"sf32\0\0\0\0".to_vec();
+    for row in 0..3 {
+        for column in &columns {
+            chad.extend_from_slice(&s15_fixed16(column[row]));
+        }
+    }
+
+    let tags: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"desc", text_tag("sRGB IEC61966-2.1 (chromatic)")),
+        (b"cprt", text_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(d50)),
+        (b"rXYZ", colorant(Color::from_rgb(255, 0, 0))),
+        (b"gXYZ", colorant(Color::from_rgb(0, 255, 0))),
+        (b"bXYZ", colorant(Color::from_rgb(0, 0, 255))),
+        (b"chad", chad),
+    ];
+    let trc_signatures = [b"rTRC", b"gTRC", b"bTRC"];
+
+    // Header, then the tag table, then 4-byte aligned tag data. The three
+    // TRC entries all point at the same curve.
+    let table_len = 4 + 12 * (tags.len() + trc_signatures.len());
+    let mut table = ((tags.len() + trc_signatures.len()) as u32)
+        .to_be_bytes()
+        .to_vec();
+    let mut data = Vec::new();
+    let mut place = |signature: &[u8; 4], body: &[u8], table: &mut Vec<u8>| {
+        let offset = 128 + table_len + data.len();
+        table.extend_from_slice(signature);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        data.extend_from_slice(body);
+        data.resize(data.len().next_multiple_of(4), 0);
+        offset
+    };
+    for (signature, body) in &tags {
+        place(signature, body, &mut table);
+    }
+    let trc_offset = place(trc_signatures[0], &trc, &mut table);
+    for signature in &trc_signatures[1..] {
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&(trc_offset as u32).to_be_bytes());
+        table.extend_from_slice(&(trc.len() as u32).to_be_bytes());
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut header = Vec::with_capacity(size);
+    header.extend_from_slice(&(size as u32).to_be_bytes());
+    header.extend_from_slice(&[0; 4]);
+    header.extend_from_slice(&[4, 0x30, 0, 0]);
+    header.extend_from_slice(b"mntrRGB XYZ ");
+    // Creation date, fixed so the profile bytes are reproducible
+    for field in [2024u16, 1, 1, 0, 0, 0] {
+        header.extend_from_slice(&field.to_be_bytes());
+    }
+    header.extend_from_slice(b"acsp");
+    // Platform, flags, manufacturer, model, attributes, rendering intent
+    header.extend_from_slice(&[0; 28]);
+    for v in d50 {
+        header.extend_from_slice(&s15_fixed16(v));
+    }
+    // Creator, profile ID and reserved bytes
+    header.resize(128, 0);
+
+    header.extend_from_slice(&table);
+    header.extend_from_slice(&data);
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A minimal RGB/XYZ profile with a D50 PCS illuminant and the given tags
+    fn profile_with_tags(tags: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut header = vec![0; 128];
+        header[16..20].copy_from_slice(b"RGB ");
+        header[20..24].copy_from_slice(b"XYZ ");
+        header[36..40].copy_from_slice(b"acsp");
+        for (i, v) in WhitePoint::D50.xyz().into_iter().enumerate() {
+            header[68 + i * 4..72 + i * 4].copy_from_slice(&s15_fixed16(v));
+        }
+
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        let data_start = 128 + 4 + 12 * tags.len();
+        for (signature, body) in tags {
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&((data_start + data.len()) as u32).to_be_bytes());
+            table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            data.extend_from_slice(body);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+        let size = (128 + table.len() + data.len()) as u32;
+        header[0..4].copy_from_slice(&size.to_be_bytes());
+        header.extend_from_slice(&table);
+        header.extend_from_slice(&data);
+        header
+    }
+
+    fn gamma_curv(gamma_u8_8: u16) -> Vec<u8> {
+        let mut body = b"curv\0\0\0\0".to_vec();
+        body.extend_from_slice(&1u32.to_be_bytes());
+        body.extend_from_slice(&gamma_u8_8.to_be_bytes());
+        body
+    }
+
+    fn para(kind: u16, params: &[f32]) -> Vec<u8> {
+        let mut body = b"para\0\0\0\0".to_vec();
+        body.extend_from_slice(&kind.to_be_bytes());
+        body.extend_from_slice(&[0, 0]);
+        for p in params {
+            body.extend_from_slice(&s15_fixed16(*p));
+        }
+        body
+    }
+
+    // Colorants and gamma (563/256) of the Adobe RGB (1998) profile
+    fn adobe_rgb_profile() -> Vec<u8> {
+        let trc = gamma_curv(563);
+        profile_with_tags(&[
+            (b"rXYZ", xyz_tag([0.609_74, 0.311_11, 0.019_47])),
+            (b"gXYZ", xyz_tag([0.205_28, 0.625_67, 0.060_87])),
+            (b"bXYZ", xyz_tag([0.149_19, 0.063_22, 0.744_57])),
+            (b"rTRC", trc.clone()),
+            (b"gTRC", trc.clone()),
+            (b"bTRC", trc),
+        ])
+    }
+
+    fn assert_close_rgb(got: &Color, expected: (u8, u8, u8)) {
+        let diff = |a: u8, b: u8| a.abs_diff(b);
+        assert!(
+            diff(got.r, expected.0) <= 1
+                && diff(got.g, expected.1) <= 1
+                && diff(got.b, expected.2) <= 1,
+            "{got:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn generated_srgb_profile_converts_to_itself() {
+        let profile = IccProfile::parse(&srgb_icc_profile()).unwrap();
+        assert!(matches!(profile.curves[0], Curve::Parametric(3, _)));
+        for r in (0..=255).step_by(51) {
+            for g in (0..=255).step_by(51) {
+                for b in (0..=255).step_by(51) {
+                    let device = [r, g, b].map(|c: u8| f32::from(c) / 255.0);
+                    assert_close_rgb(&profile.to_srgb(device), (r, g, b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adobe_rgb_matches_reference_conversions() {
+        // Reference values from the published Adobe RGB (D65) and sRGB
+        // matrices, independent of the D50 PCS route taken here
+        let profile = IccProfile::parse(&adobe_rgb_profile()).unwrap();
+        assert_eq!(profile.curves[1], Curve::Gamma(563.0 / 256.0));
+        let cases = [
+            ((255, 255, 255), (255, 255, 255)),
+            ((128, 128, 128), (129, 129, 129)),
+            ((100, 150, 80), (66, 151, 74)),
+            ((200, 120, 60), (224, 121, 53)),
+            ((60, 90, 200), (36, 90, 204)),
+        ];
+        for ((r, g, b), expected) in cases {
+            let device = [r, g, b].map(|c: u8| f32::from(c) / 255.0);
+            assert_close_rgb(&profile.to_srgb(device), expected);
+        }
+    }
+
+    #[test]
+    fn parses_every_parametric_curve_type() {
+        let cases: [(u16, &[f32], [f32; 7]); 5] = [
+            (0, &[2.0], [2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            (1, &[2.0, 2.0, -0.5], [2.0, 2.0, -0.5, 0.0, 0.0, 0.0, 0.0]),
+            (
+                2,
+                &[1.0, 1.0, 0.0, 0.25],
+                [1.0, 1.0, 0.0, 0.25, 0.0, 0.0, 0.0],
+            ),
+            (
+                3,
+                &[1.0, 1.0, 0.0, 0.5, 0.5],
+                [1.0, 1.0, 0.0, 0.5, 0.5, 0.0, 0.0],
+            ),
+            (
+                4,
+                &[1.0, 0.5, 0.25, 0.5, 0.5, 0.125, 0.0625],
+                [1.0, 0.5, 0.25, 0.5, 0.5, 0.125, 0.0625],
+            ),
+        ];
+        for (kind, params, expected) in cases {
+            assert_eq!(
+                parse_curve(&para(kind, params)).unwrap(),
+                Curve::Parametric(kind, expected)
+            );
+        }
+
+        let apply = |kind, params: &[f32], x| parse_curve(&para(kind, params)).unwrap().apply(x);
+        assert_eq!(apply(0, &[2.0], 0.5), 0.25);
+        // (2x - 0.5)^2, zero below x = 0.25
+        assert_eq!(apply(1, &[2.0, 2.0, -0.5], 0.75), 1.0);
+        assert_eq!(apply(1, &[2.0, 2.0, -0.5], 0.1), 0.0);
+        assert_eq!(apply(2, &[1.0, 1.0, 0.0, 0.25], 0.5), 0.75);
+        // Linear segment c * x below d, power segment above
+        assert_eq!(apply(3, &[1.0, 1.0, 0.0, 0.5, 0.5], 0.25), 0.125);
+        assert_eq!(apply(3, &[1.0, 1.0, 0.0, 0.5, 0.5], 0.75), 0.75);
+        let type4 = [1.0, 0.5, 0.25, 0.5, 0.5, 0.125, 0.0625];
+        assert_eq!(apply(4, &type4, 0.25), 0.1875);
+        assert_eq!(apply(4, &type4, 1.0), 0.875);
+        assert!(matches!(
+            parse_curve(&para(5, &[])),
+            Err(ColorParseError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn sampled_curves_interpolate_between_entries() {
+        let mut body = b"curv\0\0\0\0".to_vec();
+        body.extend_from_slice(&3u32.to_be_bytes());
+        for v in [0u16, 0x4000, 0xFFFF] {
+            body.extend_from_slice(&v.to_be_bytes());
+        }
+        let curve = parse_curve(&body).unwrap();
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert_eq!(curve.apply(1.0), 1.0);
+        let quarter = curve.apply(0.25);
+        assert!((quarter - 0.125).abs() < 1e-4, "{quarter}");
+        assert_eq!(
+            parse_curve(b"curv\0\0\0\0\0\0\0\0").unwrap(),
+            Curve::Identity
+        );
+    }
+
+    #[test]
+    fn rejects_lut_only_and_malformed_profiles() {
+        let lut_only = profile_with_tags(&[(b"A2B0", vec![0; 32])]);
+        assert!(matches!(
+            IccProfile::parse(&lut_only),
+            Err(ColorParseError::Unsupported(_))
+        ));
+        assert!(matches!(
+            IccProfile::parse(&[0; 200]),
+            Err(ColorParseError::Malformed(_))
+        ));
+        // A tag count far larger than the file must not be trusted
+        let mut huge_count = profile_with_tags(&[]);
+        huge_count[128..132].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            IccProfile::parse(&huge_count),
+            Err(ColorParseError::Malformed(_))
+        ));
+        let mut truncated = adobe_rgb_profile();
+        truncated.truncate(200);
+        assert!(matches!(
+            IccProfile::parse(&truncated),
+            Err(ColorParseError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn generated_srgb_profile_has_a_valid_header() {
+        let bytes = srgb_icc_profile();
+        assert_eq!(bytes[36..40], 0x6163_7370u32.to_be_bytes());
+        assert_eq!(&bytes[36..40], b"acsp");
+        assert_eq!(bytes[0..4], (bytes.len() as u32).to_be_bytes());
+        // Version 4, RGB data in an XYZ connection space
+        assert_eq!(bytes[8], 4);
+        assert_eq!(&bytes[16..20], b"RGB ");
+        assert_eq!(&bytes[20..24], b"XYZ ");
+    }
+}
@@ -0,0 +1,258 @@
+use crate::icc;
+use crate::Color;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// Wrap data in a zlib stream of stored (uncompressed) deflate blocks. It
+// is bigger than real compression but needs no encoder.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(usize::from(u16::MAX)).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(u8::from(blocks.peek().is_none()));
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Insert an `iCCP` chunk carrying the sRGB profile from
+// `icc::srgb_icc_profile` before the first IDAT chunk. Any existing `iCCP`
+// or `sRGB` chunk is dropped, since PNG allows only one of them. Input that
+// isn't a well-formed PNG is returned unchanged.
+pub fn embed_icc_in_png(png_bytes: &[u8]) -> Vec<u8> {
+    if !png_bytes.starts_with(&PNG_SIGNATURE) {
+        return png_bytes.to_vec();
+    }
+    let mut iccp = b"sRGB\0\0".to_vec();
+    iccp.extend_from_slice(&zlib_stored(&icc::srgb_icc_profile()));
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut rest = &png_bytes[PNG_SIGNATURE.len()..];
+    let mut inserted = false;
+    while !rest.is_empty() {
+        let Some(len) = rest
+            .get(..4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        else {
+            return png_bytes.to_vec();
+        };
+        let total = 12 + len as usize;
+        let (Some(chunk), Some(kind)) = (rest.get(..total), rest.get(4..8)) else {
+            return png_bytes.to_vec();
+        };
+        if kind == b"IDAT" && !inserted {
+            png_chunk(&mut out, b"iCCP", &iccp);
+            inserted = true;
+        }
+        if kind != b"iCCP" && kind != b"sRGB" {
+            out.extend_from_slice(chunk);
+        }
+        rest = &rest[total..];
+    }
+    out
+}
+
+impl Color {
+    // Build a 24-bit uncompressed TGA file that is a solid `width` x
+    // `height` rectangle of the color. TGA stores pixels as BGR.
+    pub fn to_tga_swatch(&self, width: u16, height: u16) -> Vec<u8> {
+        let [w_lo, w_hi] = width.to_le_bytes();
+        let [h_lo, h_hi] = height.to_le_bytes();
+        // No ID or color map, image type 2 (uncompressed true color), origin
+        // at 0,0, 24 bits per pixel and no alpha bits in the descriptor
+        let header = [
+            0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, w_lo, w_hi, h_lo, h_hi, 24, 0,
+        ];
+
+        let pixels = usize::from(width) * usize::from(height);
+        let mut out = Vec::with_capacity(header.len() + pixels * 3);
+        out.extend_from_slice(&header);
+        for _ in 0..pixels {
+            out.extend_from_slice(&[self.b, self.g, self.r]);
+        }
+        out
+    }
+
+    // Build an ASCII (P3) PPM image that is a solid `width` x `height`
+    // rectangle of the color. Lines are kept under the 70 characters the
+    // format recommends by writing one pixel per line.
+    pub fn to_ppm_swatch(&self, width: u32, height: u32) -> String {
+        let pixel = format!("{} {} {}\n", self.r, self.g, self.b);
+        let mut out = format!("P3\n{width} {height}\n255\n");
+        out.push_str(&pixel.repeat(width as usize * height as usize));
+        out
+    }
+
+    // Build a binary (P6) PPM image that is a solid `width` x `height`
+    // rectangle of the color
+    pub fn to_ppm_swatch_binary(&self, width: u32, height: u32) -> Vec<u8> {
+        let mut out = format!("P6\n{width} {height}\n255\n").into_bytes();
+        let pixels = width as usize * height as usize;
+        out.reserve(pixels * 3);
+        for _ in 0..pixels {
+            out.extend_from_slice(&[self.r, self.g, self.b]);
+        }
+        out
+    }
+
+    // Build an 8-bit RGB PNG that is a solid `width` x `height` rectangle
+    // of the color. The image data is stored uncompressed.
+    pub fn to_color_swatch_png_bytes(&self, width: u32, height: u32) -> Vec<u8> {
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        // Bit depth 8, color type 2 (RGB), default compression, filtering
+        // and no interlacing
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        // Each scanline starts with filter type 0 (none)
+        let mut row = vec![0u8];
+        for _ in 0..width {
+            row.extend_from_slice(&[self.r, self.g, self.b]);
+        }
+        let raw = row.repeat(height as usize);
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        png_chunk(&mut out, b"IHDR", &ihdr);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tga_swatch_has_spec_header_and_bgr_pixels() {
+        let tga = Color::from_rgb(0x11, 0x22, 0x33).to_tga_swatch(0x0102, 3);
+        assert_eq!(
+            tga[..18],
+            [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02, 0x01, 3, 0, 24, 0]
+        );
+        assert_eq!(tga.len(), 18 + 0x0102 * 3 * 3);
+        assert!(tga[18..].chunks(3).all(|pixel| pixel == [0x33, 0x22, 0x11]));
+    }
+
+    #[test]
+    fn empty_tga_swatch_is_just_the_header() {
+        let tga = Color::from_rgb(1, 2, 3).to_tga_swatch(0, 5);
+        assert_eq!(tga.len(), 18);
+        assert_eq!(tga[12..16], [0, 0, 5, 0]);
+    }
+
+    #[test]
+    fn ascii_ppm_swatch_has_header_lines() {
+        let ppm = Color::from_rgb(255, 128, 0).to_ppm_swatch(3, 2);
+        let lines: Vec<&str> = ppm.lines().collect();
+        assert_eq!(lines[..3], ["P3", "3 2", "255"]);
+        assert_eq!(lines.len(), 3 + 3 * 2);
+        assert!(lines[3..].iter().all(|&pixel| pixel == "255 128 0"));
+        assert!(lines.iter().all(|line| line.len() <= 70));
+    }
+
+    #[test]
+    fn binary_ppm_swatch_has_header_and_rgb_pixels() {
+        let ppm = Color::from_rgb(1, 2, 3).to_ppm_swatch_binary(4, 5);
+        let header = b"P6\n4 5\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 4 * 5 * 3);
+        assert!(ppm[header.len()..]
+            .chunks(3)
+            .all(|pixel| pixel == [1, 2, 3]));
+    }
+
+    // Chunk types of a PNG in file order, checking each chunk's CRC
+    fn chunk_types(png: &[u8]) -> Vec<[u8; 4]> {
+        assert!(png.starts_with(&PNG_SIGNATURE));
+        let mut rest = &png[PNG_SIGNATURE.len()..];
+        let mut types = Vec::new();
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (body, crc) = rest[4..12 + len].split_at(4 + len);
+            assert_eq!(crc, crc32(body).to_be_bytes());
+            types.push(body[..4].try_into().unwrap());
+            rest = &rest[12 + len..];
+        }
+        types
+    }
+
+    #[test]
+    fn embedded_iccp_comes_before_idat() {
+        let png = Color::from_rgb(255, 0, 0).to_color_swatch_png_bytes(2, 2);
+        assert_eq!(chunk_types(&png), [*b"IHDR", *b"IDAT", *b"IEND"]);
+        let tagged = embed_icc_in_png(&png);
+        assert_eq!(
+            chunk_types(&tagged),
+            [*b"IHDR", *b"iCCP", *b"IDAT", *b"IEND"]
+        );
+    }
+
+    #[test]
+    fn embedding_replaces_existing_color_chunks() {
+        let png = Color::from_rgb(255, 0, 0).to_color_swatch_png_bytes(1, 1);
+        let ihdr_end = PNG_SIGNATURE.len() + 12 + 13;
+        let mut with_srgb = png[..ihdr_end].to_vec();
+        png_chunk(&mut with_srgb, b"sRGB", &[0]);
+        with_srgb.extend_from_slice(&png[ihdr_end..]);
+
+        let tagged = embed_icc_in_png(&with_srgb);
+        assert_eq!(
+            chunk_types(&tagged),
+            [*b"IHDR", *b"iCCP", *b"IDAT", *b"IEND"]
+        );
+        // Embedding twice still leaves a single iCCP chunk
+        assert_eq!(embed_icc_in_png(&tagged), tagged);
+    }
+
+    #[test]
+    fn non_png_input_is_returned_unchanged() {
+        for input in [&b"GIF89a"[..], &PNG_SIGNATURE[..], &[]] {
+            assert_eq!(embed_icc_in_png(input), input);
+        }
+        let png = Color::from_rgb(0, 0, 0).to_color_swatch_png_bytes(1, 1);
+        let truncated = &png[..png.len() - 3];
+        assert_eq!(embed_icc_in_png(truncated), truncated);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::Color;
+
+// Entries along each axis of a Hald CLUT of the given level
+fn hald_size(level: u8) -> usize {
+    usize::from(level) * usize::from(level)
+}
+
+#[allow(dead_code)]
+// The identity Hald CLUT of a level, red varying fastest then green then
+// blue, the same order its pixels are read from a level³ × level³ image.
+// Levels below 2 have fewer than two entries per axis and give an empty
+// table, which `apply_haldclut` would ignore anyway.
+pub fn haldclut_identity(level: u8) -> Vec<Color> {
+    let size = hald_size(level);
+    if size < 2 {
+        return Vec::new();
+    }
+    let scale = |i: usize| (i as f32 * 255.0 / (size - 1) as f32).round() as u8;
+    (0..size * size * size)
+        .map(|i| {
+            Color::from_rgb(
+                scale(i % size),
+                scale(i / size % size),
+                scale(i / (size * size)),
+            )
+        })
+        .collect()
+}
+
+impl Color {
+    #[allow(dead_code)]
+    // Look `input` up in a Hald CLUT of `lut_level`, given as its pixels in
+    // image order, blending the eight surrounding entries trilinearly. A
+    // table with too few entries leaves the color unchanged.
+    pub fn apply_haldclut(input: &Color, lut_pixels: &[Color], lut_level: u8) -> Color {
+        let size = hald_size(lut_level);
+        if size < 2 || lut_pixels.len() < size * size * size {
+            return input.clone();
+        }
+
+        // Lower grid index and the fraction towards the next one
+        let axis = |channel: u8| {
+            let position = f32::from(channel) / 255.0 * (size - 1) as f32;
+            let low = (position.floor() as usize).min(size - 2);
+            (low, position - low as f32)
+        };
+        let (r0, fr) = axis(input.r);
+        let (g0, fg) = axis(input.g);
+        let (b0, fb) = axis(input.b);
+
+        let mut sum = [0.0f32; 3];
+        for corner in 0..8 {
+            let (dr, dg, db) = (corner & 1, corner >> 1 & 1, corner >> 2 & 1);
+            let weight = (if dr == 1 { fr } else { 1.0 - fr })
+                * (if dg == 1 { fg } else { 1.0 - fg })
+                * (if db == 1 { fb } else { 1.0 - fb });
+            let entry = &lut_pixels[(r0 + dr) + (g0 + dg) * size + (b0 + db) * size * size];
+            sum[0] += weight * f32::from(entry.r);
+            sum[1] += weight * f32::from(entry.g);
+            sum[2] += weight * f32::from(entry.b);
+        }
+
+        let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+        Color::from_rgb(channel(sum[0]), channel(sum[1]), channel(sum[2]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_lut_leaves_colors_unchanged() {
+        for level in [2, 4, 8] {
+            let lut = haldclut_identity(level);
+            let size = hald_size(level);
+            assert_eq!(lut.len(), size * size * size);
+            for r in (0..=255).step_by(15) {
+                for g in (0..=255).step_by(17) {
+                    for b in (0..=255).step_by(51) {
+                        let color = Color::from_rgb(r, g, b);
+                        assert_eq!(Color::apply_haldclut(&color, &lut, level), color);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn identity_lut_is_in_image_order() {
+        let lut = haldclut_identity(2);
+        assert_eq!(lut[0], Color::from_rgb(0, 0, 0));
+        assert_eq!(lut[1], Color::from_rgb(85, 0, 0));
+        assert_eq!(lut[4], Color::from_rgb(0, 85, 0));
+        assert_eq!(lut[16], Color::from_rgb(0, 0, 85));
+        assert_eq!(lut[63], Color::from_rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn degenerate_luts_are_ignored() {
+        assert!(haldclut_identity(0).is_empty());
+        assert!(haldclut_identity(1).is_empty());
+        let color = Color::from_rgb(10, 20, 30);
+        assert_eq!(Color::apply_haldclut(&color, &[], 0), color);
+        let short = &haldclut_identity(2)[..63];
+        assert_eq!(Color::apply_haldclut(&color, short, 2), color);
+        assert_eq!(
+            Color::apply_haldclut(&color, &haldclut_identity(4), 1),
+            color
+        );
+    }
+}
@@ -1,7 +1,34 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
+mod a11y;
+mod adjust;
+mod animation;
+mod css;
+mod error;
+mod formats;
+mod icc;
+mod image;
+mod json;
+mod lut;
+mod mix;
+mod ok_color;
+mod palette;
+mod ral;
+mod report;
+mod scheme;
+mod space;
+mod temperature;
+mod terminal;
+mod theme;
+mod xml;
+
+use error::ColorError;
+use scheme::Scheme;
+use space::WhitePoint;
+use std::io::IsTerminal;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Color {
     r: u8,
     g: u8,
@@ -14,20 +41,87 @@ impl Color {
         Self { r, g, b }
     }
 
+    #[allow(dead_code)]
+    // Constructor from wider integers, or `None` if any is above 255
+    pub fn checked_from_rgb_u32(r: u32, g: u32, b: u32) -> Option<Self> {
+        Some(Self::from_rgb(
+            r.try_into().ok()?,
+            g.try_into().ok()?,
+            b.try_into().ok()?,
+        ))
+    }
+
+    #[allow(dead_code)]
+    // Constructor from wider integers, clamping each to 255
+    pub fn saturating_from_rgb_u32(r: u32, g: u32, b: u32) -> Self {
+        let clamp = |v: u32| v.min(255) as u8;
+        Self::from_rgb(clamp(r), clamp(g), clamp(b))
+    }
+
     // Constructor from HEX code
-    pub fn from_hex(hex: &str) -> Result<Self, &'static str> {
+    pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
         let hex = hex.trim_start_matches('#');
+        // The digits are sliced by byte below, which would panic inside a
+        // multi-byte character
+        if !hex.is_ascii() {
+            return Err(ColorError::InvalidHexDigit { position: 0 });
+        }
         if hex.len() != 6 {
-            return Err("Hex code must be 6 characters long");
+            return Err(ColorError::InvalidHexLength { len: hex.len() });
         }
 
-        let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| "Invalid hex code")?;
-        let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| "Invalid hex code")?;
-        let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| "Invalid hex code")?;
+        let channel = |start: usize| {
+            let pair = &hex[start..start + 2];
+            u8::from_str_radix(pair, 16).map_err(|_| {
+                let offset = pair.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(0);
+                ColorError::InvalidHexDigit {
+                    position: start + offset,
+                }
+            })
+        };
+        let r = channel(0)?;
+        let g = channel(2)?;
+        let b = channel(4)?;
 
         Ok(Self { r, g, b })
     }
 
+    // Parse every item of a `separator`-delimited list of hex codes,
+    // keeping going past invalid items
+    pub fn from_hex_batch(input: &str, separator: char) -> Vec<Result<Color, ColorError>> {
+        input
+            .split(separator)
+            .map(|item| Color::from_hex(item.trim()))
+            .collect()
+    }
+
+    // Like `from_hex`, but rejects lowercase digits (`#ff0000`)
+    pub fn from_hex_strict_upper(hex: &str) -> Result<Self, ColorError> {
+        let color = Self::from_hex(hex)?;
+        if hex.bytes().any(|c| c.is_ascii_lowercase()) {
+            return Err(ColorError::NotUppercase);
+        }
+        Ok(color)
+    }
+
+    // Like `from_hex`, but rejects uppercase digits (`#FF0000`)
+    pub fn from_hex_strict_lower(hex: &str) -> Result<Self, ColorError> {
+        let color = Self::from_hex(hex)?;
+        if hex.bytes().any(|c| c.is_ascii_uppercase()) {
+            return Err(ColorError::NotLowercase);
+        }
+        Ok(color)
+    }
+
+    #[allow(dead_code)]
+    // Like `from_hex_batch`, but fails on the first invalid item
+    pub fn from_hex_batch_strict(input: &str, separator: char) -> Result<Vec<Color>, ColorError> {
+        input
+            .split(separator)
+            .map(|item| Color::from_hex(item.trim()))
+            .collect()
+    }
+
     // Constructor from HSV values
     pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
         let c = v * s;
@@ -51,7 +145,6 @@ impl Color {
         Self { r, g, b }
     }
 
-    #[allow(dead_code)]
     // Convert to HEX string
     pub fn to_hex(&self) -> String {
         format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
@@ -63,6 +156,83 @@ impl Color {
         (self.r, self.g, self.b)
     }
 
+    #[allow(dead_code)]
+    // Constructor from the first three bytes of a buffer, in R, G, B order
+    pub fn from_u8_slice(slice: &[u8]) -> Result<Self, ColorError> {
+        match slice {
+            [r, g, b, ..] => Ok(Self::from_rgb(*r, *g, *b)),
+            _ => Err(ColorError::SliceTooShort {
+                expected: 3,
+                got: slice.len(),
+            }),
+        }
+    }
+
+    #[allow(dead_code)]
+    // Constructor from the first three bytes of a buffer, in B, G, R order
+    pub fn from_u8_slice_bgr(slice: &[u8]) -> Result<Self, ColorError> {
+        let swapped = Self::from_u8_slice(slice)?;
+        Ok(Self::from_rgb(swapped.b, swapped.g, swapped.r))
+    }
+
+    #[allow(dead_code)]
+    // Write R, G, B into the first three bytes of `buf`, leaving the rest
+    pub fn to_u8_slice(&self, buf: &mut [u8]) -> Result<(), ColorError> {
+        Self::write_bytes(buf, [self.r, self.g, self.b])
+    }
+
+    #[allow(dead_code)]
+    // Write B, G, R into the first three bytes of `buf`, leaving the rest
+    pub fn to_u8_slice_bgr(&self, buf: &mut [u8]) -> Result<(), ColorError> {
+        Self::write_bytes(buf, [self.b, self.g, self.r])
+    }
+
+    fn write_bytes(buf: &mut [u8], bytes: [u8; 3]) -> Result<(), ColorError> {
+        let got = buf.len();
+        buf.get_mut(..3)
+            .ok_or(ColorError::SliceTooShort { expected: 3, got })?
+            .copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    // Pack as 0xRRGGBB
+    pub fn to_u32_rgb(&self) -> u32 {
+        u32::from(self.r) << 16 | u32::from(self.g) << 8 | u32::from(self.b)
+    }
+
+    #[allow(dead_code)]
+    // Six character identifier, the hex code without its `#`
+    pub fn to_stable_id(&self) -> String {
+        self.to_hex().trim_start_matches('#').to_string()
+    }
+
+    #[allow(dead_code)]
+    // Hex digits without the `#`, left-padded with `pad_char` up to
+    // `total_chars`. Longer results are never truncated.
+    pub fn to_hex_padded(&self, total_chars: usize, pad_char: char) -> String {
+        let digits = self.to_stable_id();
+        let padding: String =
+            std::iter::repeat_n(pad_char, total_chars.saturating_sub(digits.len())).collect();
+        padding + &digits
+    }
+
+    #[allow(dead_code)]
+    // Base-36 form of `to_u32_rgb`, zero-padded to at least four characters.
+    // Colors above 0x19A0FF (`zzzz`) need a fifth character to stay unique.
+    pub fn to_short_id(&self) -> String {
+        let mut value = self.to_u32_rgb();
+        let mut digits = Vec::new();
+        while value > 0 {
+            digits.push(char::from_digit(value % 36, 36).unwrap_or('0'));
+            value /= 36;
+        }
+        while digits.len() < 4 {
+            digits.push('0');
+        }
+        digits.iter().rev().collect()
+    }
+
     // Convert to HSV tuple
     pub fn to_hsv(&self) -> (f32, f32, f32) {
         let r = f32::from(self.r) / 255.0;
@@ -86,12 +256,160 @@ impl Color {
         let s = if max == 0.0 { 0.0 } else { delta / max };
         let v = max;
 
-        (h.abs(), s, v)
+        (h.rem_euclid(360.0), s, v)
+    }
+
+    #[allow(dead_code)]
+    // Constructor from HSB values. HSB (hue, saturation, brightness) is
+    // the name Photoshop, Sketch and Figma use for HSV: brightness is
+    // exactly value, so this is `from_hsv` under another name.
+    pub fn from_hsb(h: f32, s: f32, b: f32) -> Self {
+        Self::from_hsv(h, s, b)
+    }
+
+    // Convert to HSB tuple, the same values as `to_hsv` (see `from_hsb`)
+    pub fn to_hsb(&self) -> (f32, f32, f32) {
+        self.to_hsv()
+    }
+
+    // Constructor from HSL values
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let v = l + s * l.min(1.0 - l);
+        let s_v = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+        Self::from_hsv(h.rem_euclid(360.0), s_v, v)
+    }
+
+    // Convert to HSL tuple
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (h, s_v, v) = self.to_hsv();
+        let l = v * (1.0 - s_v / 2.0);
+        let s = if l == 0.0 || l == 1.0 {
+            0.0
+        } else {
+            (v - l) / l.min(1.0 - l)
+        };
+        (h, s, l)
+    }
+
+    #[allow(dead_code)]
+    // Constructor for a gray with all three channels set to `luma`
+    pub fn from_grayscale(luma: u8) -> Self {
+        Self::from_rgb(luma, luma, luma)
+    }
+
+    #[allow(dead_code)]
+    // Constructor for a gray from a gamma-encoded level in [0, 1]
+    pub fn from_grayscale_f32(luma: f32) -> Result<Self, ColorError> {
+        if !(0.0..=1.0).contains(&luma) {
+            return Err(ColorError::OutOfRange {
+                component: "luma",
+                value: luma,
+            });
+        }
+        Ok(Self::from_grayscale((luma * 255.0).round() as u8))
     }
+
+    #[allow(dead_code)]
+    // Constructor from CMYK values in [0, 1] (naive conversion, no ink
+    // profile)
+    pub fn from_cmyk(c: f32, m: f32, y: f32, k: f32) -> Self {
+        let channel = |ink: f32| {
+            ((1.0 - ink.clamp(0.0, 1.0)) * (1.0 - k.clamp(0.0, 1.0)) * 255.0).round() as u8
+        };
+        Self::from_rgb(channel(c), channel(m), channel(y))
+    }
+
+    // Convert to CMYK tuple, components in [0, 1]
+    pub fn to_cmyk(&self) -> (f32, f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        if max == 0 {
+            return (0.0, 0.0, 0.0, 1.0);
+        }
+        let k = 1.0 - f32::from(max) / 255.0;
+        let ink = |c: u8| 1.0 - f32::from(c) / f32::from(max);
+        (ink(self.r), ink(self.g), ink(self.b), k)
+    }
+
+    // Every supported notation of the color, one per line with aligned
+    // labels. Angles are in degrees, Lab and LCH are relative to D65.
+    pub fn to_all_formats_string(&self) -> String {
+        let (h, s, v) = self.to_hsv();
+        let (hl, sl, l) = self.to_hsl();
+        let (lab_l, lab_a, lab_b) = self.to_lab();
+        let (lch_l, lch_c, lch_h) = self.to_lch();
+        let (ok_l, ok_a, ok_b) = self.to_oklab();
+        let (okch_l, okch_c, okch_h) = self.to_oklch();
+        let (c, m, y, k) = self.to_cmyk();
+        let lines = [
+            ("Hex", self.to_hex()),
+            ("RGB", format!("{}, {}, {}", self.r, self.g, self.b)),
+            (
+                "HSV",
+                format!("{h:.1}°, {:.1}%, {:.1}%", s * 100.0, v * 100.0),
+            ),
+            (
+                "HSL",
+                format!("{hl:.1}°, {:.1}%, {:.1}%", sl * 100.0, l * 100.0),
+            ),
+            ("Lab", format!("{lab_l:.2}, {lab_a:.2}, {lab_b:.2}")),
+            ("LCH", format!("{lch_l:.2}, {lch_c:.2}, {lch_h:.2}°")),
+            ("Oklab", format!("{ok_l:.4}, {ok_a:.4}, {ok_b:.4}")),
+            ("OKLCH", format!("{okch_l:.4}, {okch_c:.4}, {okch_h:.2}°")),
+            (
+                "CMYK",
+                format!(
+                    "{:.1}%, {:.1}%, {:.1}%, {:.1}%",
+                    c * 100.0,
+                    m * 100.0,
+                    y * 100.0,
+                    k * 100.0
+                ),
+            ),
+        ];
+        lines
+            .iter()
+            .map(|(label, value)| format!("{:<7}{value}\n", format!("{label}:")))
+            .collect()
+    }
+
     // Display color as ANSI escape code for terminal
     pub fn to_ansi(&self) -> String {
         format!("\x1b[48;2;{};{};{}m \x1b[0m", self.r, self.g, self.b)
     }
+
+    // SGR sequence setting a text attribute along with this foreground color
+    fn to_ansi_styled_fg(&self, attribute: u8) -> String {
+        format!("\x1b[{attribute};38;2;{};{};{}m", self.r, self.g, self.b)
+    }
+
+    #[allow(dead_code)]
+    // Bold (often also brightened) text in this color
+    pub fn to_ansi_bold_fg(&self) -> String {
+        self.to_ansi_styled_fg(1)
+    }
+
+    #[allow(dead_code)]
+    // Dim (faint) text in this color
+    pub fn to_ansi_dim_fg(&self) -> String {
+        self.to_ansi_styled_fg(2)
+    }
+
+    #[allow(dead_code)]
+    // Italic text in this color; not every terminal supports italics
+    pub fn to_ansi_italic_fg(&self) -> String {
+        self.to_ansi_styled_fg(3)
+    }
+
+    #[allow(dead_code)]
+    // Underlined text in this color
+    pub fn to_ansi_underline_fg(&self) -> String {
+        self.to_ansi_styled_fg(4)
+    }
+
+    // SGR sequence clearing every color and text attribute
+    pub fn reset_all() -> &'static str {
+        "\x1b[0m"
+    }
 }
 
 impl std::fmt::Display for Color {
@@ -100,6 +418,17 @@ impl std::fmt::Display for Color {
     }
 }
 
+impl FromStr for Color {
+    type Err = ColorError;
+
+    // Parse a color written as a hex code or a CSS color function. Alpha is
+    // dropped and out-of-gamut colors are clipped; use
+    // `css::parse_css_color` to find out about either.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        css::parse_css_color(s).map(|parsed| parsed.color)
+    }
+}
+
 fn rgb_complement(color: Color) -> Color {
     Color {
         r: 255 - color.r,
@@ -132,6 +461,54 @@ fn main() {
                 .value_name("HEX")
                 .help("Input color as a HEX code (e.g., #RRGGBB)"),
         )
+        .arg(
+            Arg::new("strict-case")
+                .long("strict-case")
+                .value_name("CASE")
+                .value_parser(["upper", "lower", "any"])
+                .default_value("any")
+                .help("Require the --hex digits to be all upper or all lower case"),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .value_name("COLOR")
+                .value_parser(css::parse_css_color)
+                .help("Input color as a HEX code, CSS named color or CSS function (e.g. \"oklch(62.8% 0.258 29.23)\")"),
+        )
+        .arg(
+            Arg::new("yaml-color")
+                .long("yaml-color")
+                .value_name("VALUE")
+                .value_parser(Color::from_yaml_color_string)
+                .help("Input color as a YAML config value: '#RRGGBB', '0xRRGGBB' or a CSS color name, quoted or not"),
+        )
+        .arg(
+            Arg::new("hex-batch")
+                .long("hex-batch")
+                .value_name("HEX1,HEX2,...")
+                .help("Parse a comma-separated list of HEX codes and print each color"),
+        )
+        .arg(
+            Arg::new("ral")
+                .long("ral")
+                .value_name("CODE")
+                .value_parser(clap::value_parser!(u16))
+                .help("Print the approximate color of a RAL Classic code (e.g. 3020)"),
+        )
+        .arg(
+            Arg::new("palette-a11y")
+                .long("palette-a11y")
+                .value_name("FILE")
+                .help("Print the WCAG contrast of every pair in a palette file (JSON or one hex per line)"),
+        )
+        .arg(
+            Arg::new("css-mix")
+                .long("css-mix")
+                .value_names(["FROM", "TO", "PERCENT", "SPACE"])
+                .num_args(4)
+                .help("Print a CSS color-mix() with PERCENT of FROM mixed into TO in SPACE (e.g. oklab, oklch-longer)"),
+        )
         .arg(
             Arg::new("hsv")
                 .long("hsv")
@@ -139,23 +516,821 @@ fn main() {
                 .help("Input color as HSV values (Hue 0-360, Saturation 0-1, Value 0-1)")
                 .num_args(3),
         )
+        .arg(
+            Arg::new("scheme")
+                .long("scheme")
+                .value_name("SCHEME")
+                .value_parser(Scheme::from_str)
+                .help("Add a color scheme to the output: compound[:ANGLE] (default angle 30)"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .visible_alias("output")
+                .value_name("FORMAT")
+                .value_parser([
+                    "inkscape",
+                    "procreate",
+                    "lottie",
+                    "rust",
+                    "nscolor-swift",
+                    "nscolor-objc",
+                    "iterm2",
+                    "gimp-gradient",
+                ])
+                .help("Export the input color and its complements in another format (iterm2 exports a terminal scheme based on the input color)"),
+        )
+        .arg(
+            Arg::new("rust-module")
+                .long("rust-module")
+                .value_name("NAME")
+                .default_value("palette")
+                .help("Module name for --format rust"),
+        )
+        .arg(
+            Arg::new("no-palette-array")
+                .long("no-palette-array")
+                .action(ArgAction::SetTrue)
+                .help("Leave the PALETTE array out of --format rust"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::SetTrue)
+                .help("Also print XYZ, Lab, ProPhoto and color temperature for the input color"),
+        )
+        .arg(
+            Arg::new("white-point")
+                .long("white-point")
+                .value_name("WHITE")
+                .default_value("d65")
+                .value_parser(WhitePoint::from_str)
+                .help("Reference white for XYZ and Lab output (d50, d65, a, e or x,y)"),
+        )
+        .arg(
+            Arg::new("sixel")
+                .long("sixel")
+                .value_names(["WIDTH", "HEIGHT"])
+                .value_parser(clap::value_parser!(u32))
+                .help("Draw the input color as a sixel rectangle of the given size in pixels")
+                .num_args(2),
+        )
+        .arg(
+            Arg::new("kitty-color")
+                .long("kitty-color")
+                .value_name("TYPE")
+                .value_parser(["underline", "background"])
+                .help("Print the escape setting the kitty underline color or terminal background to the input color"),
+        )
+        .arg(
+            Arg::new("xcode")
+                .long("xcode")
+                .value_name("NAME")
+                .help("Write the input color as an Xcode color set, NAME.colorset/Contents.json"),
+        )
+        .arg(
+            Arg::new("display-gamut")
+                .long("display-gamut")
+                .value_name("GAMUT")
+                .default_value("srgb")
+                .value_parser(formats::DisplayGamut::from_str)
+                .help("Color space for --xcode: srgb or display-p3"),
+        )
+        .arg(
+            Arg::new("houdini")
+                .long("houdini")
+                .value_name("NAME")
+                .num_args(0..=1)
+                .default_missing_value("color")
+                .help("Print the input color for a CSS Houdini paint worklet, with custom properties named --NAME"),
+        )
+        .arg(
+            Arg::new("gnome-terminal")
+                .long("gnome-terminal")
+                .value_name("NAME")
+                .help("Print a gsettings script creating a GNOME Terminal profile NAME colored after the input color"),
+        )
+        .arg(
+            Arg::new("alacritty")
+                .long("alacritty")
+                .action(ArgAction::SetTrue)
+                .help("Print an Alacritty [colors] table colored after the input color"),
+        )
+        .arg(
+            Arg::new("windows-terminal")
+                .long("windows-terminal")
+                .value_name("NAME")
+                .help("Print a Windows Terminal color scheme NAME colored after the input color"),
+        )
+        .arg(
+            Arg::new("excel")
+                .long("excel")
+                .action(ArgAction::SetTrue)
+                .help("Print the input color as an Excel ARGB value and cell fill XML"),
+        )
+        .arg(
+            Arg::new("pdf")
+                .long("pdf")
+                .action(ArgAction::SetTrue)
+                .help("Print the input color as PDF fill, stroke and CMYK operators"),
+        )
+        .arg(
+            Arg::new("postscript")
+                .long("postscript")
+                .action(ArgAction::SetTrue)
+                .help("Print the input color as PostScript setrgbcolor and sethsbcolor operators"),
+        )
+        .arg(
+            Arg::new("godot4")
+                .long("godot4")
+                .action(ArgAction::SetTrue)
+                .help("Print the input color as a Godot 4 Color.html() hex and Color() constructor"),
+        )
+        .arg(
+            Arg::new("latex")
+                .long("latex")
+                .value_name("NAME")
+                .help("Print a LaTeX \\definecolor for the input color named NAME and a TikZ fill using it"),
+        )
+        .arg(
+            Arg::new("highcharts")
+                .long("highcharts")
+                .action(ArgAction::SetTrue)
+                .help("Print Highcharts options for the input color and a color axis towards its complement"),
+        )
+        .arg(
+            Arg::new("pwa")
+                .long("pwa")
+                .action(ArgAction::SetTrue)
+                .help("Print web app manifest members and HTML meta tags using the input color"),
+        )
+        .arg(
+            Arg::new("spotify")
+                .long("spotify")
+                .action(ArgAction::SetTrue)
+                .help("Print an album-art style palette (background, text and two accents) built around the input color"),
+        )
+        .arg(
+            Arg::new("branding")
+                .long("branding")
+                .action(ArgAction::SetTrue)
+                .help("Print a branding kit built from the input color as CSS custom properties"),
+        )
+        .arg(
+            Arg::new("js-object")
+                .long("js-object")
+                .action(ArgAction::SetTrue)
+                .help("Print the input color as a JavaScript object literal"),
+        )
+        .arg(
+            Arg::new("set-lightness")
+                .long("set-lightness")
+                .value_name("L_STAR")
+                .value_parser(clap::value_parser!(f32))
+                .help("Print the input color moved to CIE lightness L_STAR (0-100), keeping its hue and chroma"),
+        )
+        .arg(
+            Arg::new("tga")
+                .long("tga")
+                .value_name("FILE")
+                .help("Write the input color as a solid TGA swatch"),
+        )
+        .arg(
+            Arg::new("ppm")
+                .long("ppm")
+                .value_name("FILE")
+                .help("Write the input color as a solid binary (P6) PPM swatch"),
+        )
+        .arg(
+            Arg::new("ppm-ascii")
+                .long("ppm-ascii")
+                .action(ArgAction::SetTrue)
+                .requires("ppm")
+                .help("Write --ppm as ASCII (P3) instead"),
+        )
+        .arg(
+            Arg::new("png")
+                .long("png")
+                .value_name("FILE")
+                .help("Write the input color as a solid PNG swatch tagged with an sRGB ICC profile"),
+        )
+        .arg(
+            Arg::new("swatch-size")
+                .long("swatch-size")
+                .value_names(["WIDTH", "HEIGHT"])
+                .value_parser(clap::value_parser!(u16))
+                .default_values(["64", "64"])
+                .help("Size in pixels of image swatches")
+                .num_args(2),
+        )
+        .arg(
+            Arg::new("accessible-on")
+                .long("accessible-on")
+                .value_name("BG_HEX")
+                .value_parser(Color::from_str)
+                .help("List text colors that pass WCAG AA on this background"),
+        )
+        .arg(
+            Arg::new("step")
+                .long("step")
+                .value_name("DEGREES")
+                .default_value("15")
+                .value_parser(clap::value_parser!(f32))
+                .help("Hue step for --accessible-on"),
+        )
+        .arg(
+            Arg::new("web-safe")
+                .long("web-safe")
+                .action(ArgAction::SetTrue)
+                .help("Snap to the nearest web-safe color and report the ΔE of the snap"),
+        )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .value_name("FILE")
+                .requires("web-safe")
+                .help(
+                    "Apply --web-safe to every color in a palette file (JSON or one hex per line)",
+                ),
+        )
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("report")
+                .about("Write a Markdown accessibility report for a palette")
+                .arg(
+                    Arg::new("palette")
+                        .long("palette")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Palette JSON file: {\"name\": \"#hex\"} or [{\"name\", \"hex\"}]"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write the report to FILE instead of stdout"),
+                )
+                .arg(
+                    Arg::new("no-contrast")
+                        .long("no-contrast")
+                        .action(ArgAction::SetTrue)
+                        .help("Leave out the contrast grid"),
+                )
+                .arg(
+                    Arg::new("no-cvd")
+                        .long("no-cvd")
+                        .action(ArgAction::SetTrue)
+                        .help("Leave out the color vision deficiency findings"),
+                ),
+        )
+        .subcommand(
+            Command::new("animate")
+                .about("Animate a color operation in the terminal")
+                .arg(
+                    Arg::new("color")
+                        .value_name("COLOR")
+                        .required(true)
+                        .value_parser(Color::from_str)
+                        .help("Starting color as a HEX code"),
+                )
+                .arg(
+                    Arg::new("op")
+                        .long("op")
+                        .value_name("OPERATION")
+                        .default_value("rotate:360")
+                        .value_parser(animation::Operation::from_str)
+                        .help("rotate:DEGREES, lighten:AMOUNT, darken:AMOUNT or gradient:COLOR"),
+                )
+                .arg(
+                    Arg::new("duration")
+                        .long("duration")
+                        .value_name("DURATION")
+                        .default_value("5s")
+                        .value_parser(animation::parse_duration)
+                        .help("How long the animation runs (e.g. 5s, 750ms)"),
+                )
+                .arg(
+                    Arg::new("space")
+                        .long("space")
+                        .value_name("SPACE")
+                        .default_value("srgb")
+                        .value_parser(mix::InterpolationSpace::from_str)
+                        .help("Space a gradient blends in: srgb, linear, lab, oklab, hsl, hsv or oklch (hue spaces take -shorter, -longer, -increasing or -decreasing)"),
+                )
+                .arg(
+                    Arg::new("fps")
+                        .long("fps")
+                        .value_name("FPS")
+                        .default_value("24")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .help("Frames per second (capped at 60)"),
+                ),
+        )
+        .subcommand(
+            Command::new("average-colors")
+                .about("Average colors in linear light, weighting each with a :WEIGHT suffix")
+                .arg(
+                    Arg::new("colors")
+                        .value_name("COLOR[:WEIGHT]")
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(mix::parse_weighted_color)
+                        .help("Colors to average, e.g. \"#ff0000:2\" (weight defaults to 1)"),
+                )
+                .arg(
+                    Arg::new("lab")
+                        .long("lab")
+                        .action(ArgAction::SetTrue)
+                        .help("Average in CIELAB instead of linear light"),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert device RGB values through an ICC profile to sRGB")
+                .arg(
+                    Arg::new("icc")
+                        .long("icc")
+                        .value_name("PROFILE")
+                        .required(true)
+                        .help("Matrix/TRC ICC profile the values are tagged with"),
+                )
+                .arg(
+                    Arg::new("values")
+                        .value_names(["R", "G", "B"])
+                        .required(true)
+                        .num_args(3)
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Device RGB values (0-255)"),
+                )
+                .arg(
+                    Arg::new("white-point")
+                        .long("white-point")
+                        .value_name("WHITE")
+                        .value_parser(WhitePoint::from_str)
+                        .help("Also print XYZ and Lab relative to this white (d50, d65, a, e or x,y)"),
+                ),
+        )
         .get_matches();
 
+    match matches.subcommand() {
+        Some(("report", sub)) => return run_report(sub),
+        Some(("convert", sub)) => return run_convert(sub),
+        Some(("animate", sub)) => return run_animate(sub),
+        Some(("average-colors", sub)) => return run_average(sub),
+        _ => {}
+    }
+
+    if let Some(bg) = matches.get_one::<Color>("accessible-on") {
+        let step = *matches.get_one::<f32>("step").unwrap();
+        for text in a11y::accessible_text_colors_for_background(bg, step) {
+            println!(
+                "{} {:>5.2}:1 \x1b[48;2;{};{};{}m\x1b[38;2;{};{};{}m Aa \x1b[0m",
+                text.to_hex(),
+                text.contrast_ratio(bg),
+                bg.r,
+                bg.g,
+                bg.b,
+                text.r,
+                text.g,
+                text.b
+            );
+        }
+        return;
+    }
+
+    if let Some(values) = matches.get_many::<String>("css-mix") {
+        let values: Vec<&str> = values.map(String::as_str).collect();
+        let parsed = css_mix_expression(&values);
+        match parsed {
+            Ok(expression) => println!("{expression}"),
+            Err(e) => {
+                eprintln!("Invalid --css-mix: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(&code) = matches.get_one::<u16>("ral") {
+        match (Color::from_ral_classic(code), ral::ral_classic_name(code)) {
+            (Some(c), Some(name)) => {
+                println!("RAL {code} {name}: {c} {} {}", c.to_hex(), c.to_ansi())
+            }
+            _ => {
+                eprintln!("Unknown RAL Classic code {code}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(list) = matches.get_one::<String>("hex-batch") {
+        let mut failed = false;
+        for (item, parsed) in list.split(',').zip(Color::from_hex_batch(list, ',')) {
+            match parsed {
+                Ok(c) => println!("{}: {c} {}", c.to_hex(), c.to_ansi()),
+                Err(e) => {
+                    eprintln!("{}: {e}", item.trim());
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = matches.get_one::<String>("palette-a11y") {
+        let palette = read_palette_file(path);
+        let named: Vec<(&str, Color)> = palette
+            .iter()
+            .map(|(name, c)| (name.as_str(), c.clone()))
+            .collect();
+        let report = a11y::palette_contrast_report(&named);
+        let width = named
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let mark = |passes: bool| if passes { "pass" } else { "fail" };
+        println!(
+            "{:<width$}  {:<width$}  {:>7}  AA    AAA",
+            "A", "B", "Ratio"
+        );
+        for entry in &report {
+            println!(
+                "{:<width$}  {:<width$}  {:>7}  {}  {}",
+                entry.a_name,
+                entry.b_name,
+                format!("{:.2}:1", entry.ratio),
+                mark(entry.passes_aa),
+                mark(entry.passes_aaa)
+            );
+        }
+        return;
+    }
+
+    if let Some(path) = matches.get_one::<String>("batch") {
+        let palette = read_palette_file(path);
+        let mut worst: Option<(&str, f32)> = None;
+        for (name, c) in &palette {
+            let snapped = c.to_web_safe();
+            let distance = c.delta_e(&snapped);
+            println!(
+                "{name}: {} -> {} (ΔE00 {distance:.2}) {} {}",
+                c.to_hex(),
+                snapped.to_hex(),
+                c.to_ansi(),
+                snapped.to_ansi()
+            );
+            if worst.is_none_or(|(_, d)| distance > d) {
+                worst = Some((name, distance));
+            }
+        }
+        if let Some((name, distance)) = worst {
+            println!("Worst-case deviation: {name} (ΔE00 {distance:.2})");
+        }
+        return;
+    }
+
     let color = if let Some(values) = matches.get_many::<String>("rgb") {
-        let values: Vec<u8> = values.map(|v| u8::from_str(v).unwrap()).collect();
+        let values: Vec<u8> = values
+            .map(|v| {
+                u8::from_str(v).unwrap_or_else(|_| {
+                    eprintln!("Invalid RGB value `{v}`: expected an integer from 0 to 255");
+                    std::process::exit(1);
+                })
+            })
+            .collect();
         Color::from_rgb(values[0], values[1], values[2])
     } else if let Some(hex) = matches.get_one::<String>("hex") {
-        Color::from_hex(hex).expect("Invalid HEX value")
+        let parsed = match matches.get_one::<String>("strict-case").unwrap().as_str() {
+            "upper" => Color::from_hex_strict_upper(hex),
+            "lower" => Color::from_hex_strict_lower(hex),
+            _ => Color::from_hex(hex),
+        };
+        match parsed {
+            Ok(color) => color,
+            Err(e) => {
+                eprintln!("Invalid HEX value `{hex}`: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(color) = matches.get_one::<Color>("yaml-color") {
+        color.clone()
+    } else if let Some(parsed) = matches.get_one::<css::CssColor>("color") {
+        if parsed.clipped {
+            eprintln!(
+                "Warning: the color is outside the sRGB gamut and was clipped to {}",
+                parsed.color.to_hex()
+            );
+        }
+        parsed.color.clone()
     } else if let Some(values) = matches.get_many::<String>("hsv") {
-        let values: Vec<f32> = values.map(|v| f32::from_str(v).unwrap()).collect();
+        let values: Vec<f32> = values
+            .map(|v| {
+                f32::from_str(v).unwrap_or_else(|_| {
+                    eprintln!("Invalid HSV value `{v}`: expected a number");
+                    std::process::exit(1);
+                })
+            })
+            .collect();
         Color::from_hsv(values[0], values[1], values[2])
     } else {
         eprintln!("No color input provided.");
         return;
     };
+
+    if let Some(size) = matches.get_many::<u32>("sixel") {
+        let size: Vec<u32> = size.copied().collect();
+        println!(
+            "{}",
+            terminal::color_to_sixel_swatch(&color, size[0], size[1])
+        );
+        return;
+    }
+
+    if let Some(kind) = matches.get_one::<String>("kitty-color") {
+        match kind.as_str() {
+            "underline" => println!(
+                "\x1b[4m{}{}{}",
+                color.to_kitty_osc_underline_color(),
+                color.to_hex(),
+                Color::reset_all()
+            ),
+            "background" => print!("{}", color.to_kitty_bg_color()),
+            _ => unreachable!("clap only accepts known types"),
+        }
+        return;
+    }
+
+    if let Some(name) = matches.get_one::<String>("xcode") {
+        let gamut = *matches
+            .get_one::<formats::DisplayGamut>("display-gamut")
+            .unwrap();
+        let dir = std::path::PathBuf::from(format!("{name}.colorset"));
+        let written = std::fs::create_dir_all(&dir).and_then(|()| {
+            std::fs::write(
+                dir.join("Contents.json"),
+                color.to_xcode_color_json(1.0, gamut),
+            )
+        });
+        if let Err(e) = written {
+            eprintln!("Could not write {}: {e}", dir.display());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if matches.get_flag("excel") {
+        println!("{}", color.to_excel_argb_hex(0xFF));
+        println!("{}", color.to_excel_fill_xml_fragment());
+        return;
+    }
+
+    if matches.get_flag("pdf") {
+        println!("{}", color.to_pdf_devicergb());
+        println!("{}", color.to_pdf_devicergb_stroking());
+        println!("{}", color.to_pdf_devicecmyk());
+        return;
+    }
+
+    if matches.get_flag("postscript") {
+        println!("{}", color.to_postscript_rgb());
+        println!("{}", color.to_postscript_hsb());
+        return;
+    }
+
+    if let Some(name) = matches.get_one::<String>("gnome-terminal") {
+        let scheme = theme::TerminalScheme::from_base(&color, true);
+        print!(
+            "{}",
+            theme::generate_gnome_terminal_profile(
+                name,
+                &scheme.palette,
+                &scheme.foreground,
+                &scheme.background
+            )
+        );
+        return;
+    }
+
+    if matches.get_flag("alacritty") {
+        let scheme = theme::TerminalScheme::from_base(&color, true);
+        print!(
+            "{}",
+            theme::generate_alacritty_colors(
+                &scheme.palette,
+                &scheme.foreground,
+                &scheme.background
+            )
+        );
+        return;
+    }
+
+    if let Some(name) = matches.get_one::<String>("windows-terminal") {
+        let scheme = theme::TerminalScheme::from_base(&color, true);
+        print!(
+            "{}",
+            theme::generate_windows_terminal_scheme(
+                name,
+                &scheme.palette,
+                &scheme.foreground,
+                &scheme.background,
+                &color
+            )
+        );
+        return;
+    }
+
+    if matches.get_flag("godot4") {
+        println!("Color.html(\"{}\")", color.to_godot4_html_hex());
+        println!("{}", color.to_godot4_constructor(1.0));
+        return;
+    }
+
+    if let Some(name) = matches.get_one::<String>("latex") {
+        println!("{}", color.to_tikz_definecolor(name));
+        println!("{}", color.to_tikz_fill(name));
+        return;
+    }
+
+    if matches.get_flag("highcharts") {
+        let complement = rgb_complement(color.clone());
+        println!("color: \"{}\",", color.to_highcharts(None));
+        println!(
+            "colorAxis: {{ stops: {} }}",
+            formats::highcharts_color_axis(&color, &complement, 5)
+        );
+        return;
+    }
+
+    if matches.get_flag("pwa") {
+        println!("{}\n", color.to_pwa_manifest_json_fragment());
+        println!("{}", color.to_pwa_meta_tags());
+        return;
+    }
+
+    if matches.get_flag("spotify") {
+        let palette = scheme::generate_spotify_style_palette(&color);
+        for (name, c) in [
+            ("Background", &palette.background),
+            ("Text", &palette.text),
+            ("Accent 1", &palette.accent1),
+            ("Accent 2", &palette.accent2),
+        ] {
+            println!("{name}: {c} {} {}", c.to_hex(), c.to_ansi());
+        }
+        return;
+    }
+
+    if matches.get_flag("branding") {
+        print!("{}", theme::generate_branding_kit(&color).to_css_vars());
+        return;
+    }
+
+    if matches.get_flag("js-object") {
+        println!("{}", color.to_js_full_object());
+        return;
+    }
+
+    if let Some(&l_star) = matches.get_one::<f32>("set-lightness") {
+        let adjusted = color.set_perceptual_lightness(l_star);
+        let (l, c, h) = adjusted.to_lch();
+        println!(
+            "{adjusted} {} {} (L* {l:.2}, C* {c:.2}, h {h:.1}°)",
+            adjusted.to_hex(),
+            adjusted.to_ansi()
+        );
+        return;
+    }
+
+    if let Some(name) = matches.get_one::<String>("houdini") {
+        println!("{}\n", color.to_css_houdini_paint());
+        print!("{}", color.to_css_houdini_vars(name));
+        return;
+    }
+
+    let size: Vec<u16> = matches
+        .get_many::<u16>("swatch-size")
+        .unwrap()
+        .copied()
+        .collect();
+    let (width, height) = (size[0], size[1]);
+    let swatch = if let Some(path) = matches.get_one::<String>("tga") {
+        Some((path, color.to_tga_swatch(width, height)))
+    } else if let Some(path) = matches.get_one::<String>("png") {
+        let png = color.to_color_swatch_png_bytes(u32::from(width), u32::from(height));
+        Some((path, image::embed_icc_in_png(&png)))
+    } else if let Some(path) = matches.get_one::<String>("ppm") {
+        let (width, height) = (u32::from(width), u32::from(height));
+        if matches.get_flag("ppm-ascii") {
+            Some((path, color.to_ppm_swatch(width, height).into_bytes()))
+        } else {
+            Some((path, color.to_ppm_swatch_binary(width, height)))
+        }
+    } else {
+        None
+    };
+    if let Some((path, bytes)) = swatch {
+        if let Err(e) = std::fs::write(path, bytes) {
+            eprintln!("Could not write {path}: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let rgb_c = rgb_complement(color.clone());
     let hsv_c = hsv_complement(color.clone());
+
+    let mut scheme_colors: Vec<(String, Color)> = Vec::new();
+    if let Some(Scheme::Compound(angle)) = matches.get_one::<Scheme>("scheme") {
+        let [neighbor, complement, neighbor_complement] = color.compound(*angle);
+        if angle.rem_euclid(360.0) == 0.0 {
+            eprintln!("Warning: a compound angle of 0° is just the complementary pair");
+            scheme_colors.push(("Compound Complement".to_string(), complement));
+        } else {
+            scheme_colors.push((format!("Compound Neighbor ({angle:+}°)"), neighbor));
+            scheme_colors.push(("Compound Complement".to_string(), complement));
+            scheme_colors.push((
+                "Compound Neighbor Complement".to_string(),
+                neighbor_complement,
+            ));
+        }
+    }
+
+    if let Some(format) = matches.get_one::<String>("format") {
+        let mut palette = vec![
+            ("Input", color.clone()),
+            ("RGB Complement", rgb_c.clone()),
+            ("HSV Complement", hsv_c.clone()),
+        ];
+        palette.extend(scheme_colors.iter().map(|(n, c)| (n.as_str(), c.clone())));
+        match format.as_str() {
+            "inkscape" => print!(
+                "{}",
+                palette::palette_to_inkscape_xml("chromatic", &palette)
+            ),
+            "procreate" => {
+                let colors: Vec<Color> = palette.iter().map(|(_, c)| c.clone()).collect();
+                print!("{}", palette::palette_to_procreate_json(&colors))
+            }
+            "rust" => print!(
+                "{}",
+                palette::palette_to_rust_module(
+                    matches.get_one::<String>("rust-module").unwrap(),
+                    &palette,
+                    !matches.get_flag("no-palette-array"),
+                )
+            ),
+            "lottie" => {
+                for (name, c) in &palette {
+                    println!("{name}: {}", c.to_lottie_color(1.0));
+                }
+            }
+            "nscolor-swift" => {
+                for (name, c) in &palette {
+                    println!("{name}: {}", c.to_nscolor_swift(1.0));
+                }
+            }
+            "nscolor-objc" => {
+                for (name, c) in &palette {
+                    println!("{name}: {}", c.to_nscolor_objc(1.0));
+                }
+            }
+            "gimp-gradient" => {
+                let colors: Vec<Color> = palette.iter().map(|(_, c)| c.clone()).collect();
+                print!(
+                    "{}",
+                    palette::palette_to_gimp_gradient("chromatic", &colors)
+                )
+            }
+            "iterm2" => {
+                let scheme = theme::TerminalScheme::from_base(&color, true);
+                let names: Vec<String> = (0..16).map(|i| format!("Ansi {i} Color")).collect();
+                let mut colors: Vec<(&str, Color)> = names
+                    .iter()
+                    .map(String::as_str)
+                    .zip(scheme.palette.iter().cloned())
+                    .collect();
+                colors.push(("Foreground Color", scheme.foreground.clone()));
+                colors.push(("Background Color", scheme.background.clone()));
+                colors.push(("Cursor Color", color.clone()));
+                print!("{}", palette::generate_iterm2_scheme("chromatic", &colors));
+            }
+            _ => unreachable!("clap only accepts known formats"),
+        }
+        return;
+    }
+
     println!("Input Color: {color} {}", color.to_ansi());
+    for line in color.to_all_formats_string().lines() {
+        println!("  {line}");
+    }
     println!(
         "Complementary Color (RGB Complement): {} {}",
         rgb_c,
@@ -166,4 +1341,492 @@ fn main() {
         hsv_c,
         hsv_c.to_ansi()
     );
+    for (name, c) in &scheme_colors {
+        println!("{name}: {c} {}", c.to_ansi());
+    }
+    if matches.get_flag("web-safe") {
+        let snapped = color.to_web_safe();
+        println!(
+            "Web-Safe Color: {snapped} {} (ΔE00 {:.2}) {}",
+            snapped.to_hex(),
+            color.delta_e(&snapped),
+            snapped.to_ansi()
+        );
+    }
+
+    if matches.get_flag("verbose") {
+        let white = *matches.get_one::<WhitePoint>("white-point").unwrap();
+        let label = white_point_label(white);
+        let [x, y, z] = color.to_xyz_with_white(white);
+        let (l, a, b) = color.to_lab_with_white(white);
+        let (pr, pg, pb) = color.to_prophoto();
+        println!("XYZ ({label}): {x:.4}, {y:.4}, {z:.4}");
+        println!("Lab ({label}): {l:.2}, {a:.2}, {b:.2}");
+        println!("ProPhoto RGB: {pr:.4}, {pg:.4}, {pb:.4}");
+        match color.cct_duv() {
+            Some((cct, duv)) => {
+                println!("Correlated Color Temperature: ≈ {cct:.0} K (Duv {duv:+.3})")
+            }
+            None => {
+                println!("Correlated Color Temperature: n/a (too far from the Planckian locus)")
+            }
+        }
+    }
+}
+
+// FROM TO PERCENT SPACE arguments of --css-mix as a color-mix() expression
+fn css_mix_expression(values: &[&str]) -> Result<String, String> {
+    let from: Color = values[0].parse().map_err(|e: ColorError| e.to_string())?;
+    let to: Color = values[1].parse().map_err(|e: ColorError| e.to_string())?;
+    let percent: u8 = values[2]
+        .trim_end_matches('%')
+        .parse()
+        .ok()
+        .filter(|p| *p <= 100)
+        .ok_or_else(|| format!("invalid percentage `{}`", values[2]))?;
+    let space: css::MixSpace = values[3].parse()?;
+    Ok(css::color_mix_expression(&from, &to, percent, space))
+}
+
+fn read_palette_file(path: &str) -> Vec<(String, Color)> {
+    std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| palette::parse_palette(&text).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("Could not read palette {path}: {e}");
+            std::process::exit(1);
+        })
+}
+
+fn run_report(matches: &ArgMatches) {
+    let palette = read_palette_file(matches.get_one::<String>("palette").unwrap());
+
+    let options = report::ReportOptions {
+        contrast: !matches.get_flag("no-contrast"),
+        cvd: !matches.get_flag("no-cvd"),
+    };
+    let markdown = report::markdown_report(&palette, options);
+    match matches.get_one::<String>("output") {
+        Some(output) => {
+            if let Err(e) = std::fs::write(output, markdown) {
+                eprintln!("Could not write {output}: {e}");
+                std::process::exit(1);
+            }
+        }
+        None => print!("{markdown}"),
+    }
+}
+
+fn run_average(matches: &ArgMatches) {
+    let colors: Vec<(Color, f32)> = matches
+        .get_many::<(Color, f32)>("colors")
+        .unwrap()
+        .cloned()
+        .collect();
+    let space = if matches.get_flag("lab") {
+        mix::AverageSpace::Lab
+    } else {
+        mix::AverageSpace::LinearRgb
+    };
+    match Color::weighted_average_in(&colors, space) {
+        Ok(average) => println!("{} {average} {}", average.to_hex(), average.to_ansi()),
+        Err(e) => {
+            eprintln!("Could not average colors: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_convert(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("icc").unwrap();
+    let profile = std::fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| icc::IccProfile::parse(&bytes).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("Could not read ICC profile {path}: {e}");
+            std::process::exit(1);
+        });
+
+    let device: Vec<f32> = matches
+        .get_many::<u8>("values")
+        .unwrap()
+        .map(|v| f32::from(*v) / 255.0)
+        .collect();
+    let device = [device[0], device[1], device[2]];
+    let color = profile.to_srgb(device);
+    println!("sRGB Color: {color} {} {}", color.to_hex(), color.to_ansi());
+
+    if let Some(&white) = matches.get_one::<WhitePoint>("white-point") {
+        let label = white_point_label(white);
+        let xyz = profile.to_xyz_with_white(device, white);
+        let (l, a, b) = space::xyz_to_lab(xyz, white);
+        println!("XYZ ({label}): {:.4}, {:.4}, {:.4}", xyz[0], xyz[1], xyz[2]);
+        println!("Lab ({label}): {l:.2}, {a:.2}, {b:.2}");
+    }
+}
+
+fn run_animate(matches: &ArgMatches) {
+    let color = matches.get_one::<Color>("color").unwrap();
+    let mut operation = matches
+        .get_one::<animation::Operation>("op")
+        .unwrap()
+        .clone();
+    if let animation::Operation::Gradient(_, space) = &mut operation {
+        *space = *matches.get_one::<mix::InterpolationSpace>("space").unwrap();
+    }
+    let duration = *matches.get_one::<std::time::Duration>("duration").unwrap();
+    let mut fps = *matches.get_one::<u32>("fps").unwrap();
+    if fps > animation::MAX_FPS {
+        eprintln!("Warning: capping {fps} FPS to {}", animation::MAX_FPS);
+        fps = animation::MAX_FPS;
+    }
+
+    let frames = animation::frames(color, &operation, duration, fps);
+    // parse_duration caps the duration, so the frame count always fits
+    let Ok(count) = u32::try_from(frames.len()) else {
+        eprintln!("Animation has too many frames: {}", frames.len());
+        std::process::exit(1);
+    };
+    let frame_time = duration / count;
+    if let Err(e) = animation::play(&frames, frame_time, std::io::stdout().is_terminal()) {
+        eprintln!("Animation failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn white_point_label(white: WhitePoint) -> String {
+    match white {
+        WhitePoint::D50 => "D50".to_string(),
+        WhitePoint::D65 => "D65".to_string(),
+        WhitePoint::A => "A".to_string(),
+        WhitePoint::E => "E".to_string(),
+        WhitePoint::Custom { x, y } => format!("x={x}, y={y}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hex_rejects_non_ascii_without_panicking() {
+        let inputs = [
+            // Emoji
+            "🔴0000",
+            "#🔴🔴",
+            "ff00🟦",
+            // Cyrillic, including six-byte strings whose byte slicing would
+            // split a character
+            "ж0000",
+            "0ж000",
+            "ффф000",
+            // CJK
+            "红00000",
+            "a中bc",
+            "#色色",
+        ];
+        for input in inputs {
+            assert_eq!(
+                Color::from_hex(input),
+                Err(ColorError::InvalidHexDigit { position: 0 }),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn from_hex_reports_ascii_errors() {
+        assert_eq!(
+            Color::from_hex("#1a2B3c"),
+            Ok(Color::from_rgb(0x1a, 0x2b, 0x3c))
+        );
+        assert_eq!(
+            Color::from_hex("#12345"),
+            Err(ColorError::InvalidHexLength { len: 5 })
+        );
+        assert_eq!(
+            Color::from_hex("12g456"),
+            Err(ColorError::InvalidHexDigit { position: 2 })
+        );
+    }
+
+    #[test]
+    fn strict_case_hex_parsing() {
+        assert_eq!(
+            Color::from_hex_strict_upper("#ff0000"),
+            Err(ColorError::NotUppercase)
+        );
+        assert_eq!(
+            Color::from_hex_strict_upper("#FF0000"),
+            Ok(Color::from_rgb(255, 0, 0))
+        );
+        assert_eq!(
+            Color::from_hex_strict_lower("#FF0000"),
+            Err(ColorError::NotLowercase)
+        );
+        assert_eq!(
+            Color::from_hex_strict_lower("#ff0000"),
+            Ok(Color::from_rgb(255, 0, 0))
+        );
+        // Digit-only codes satisfy both, and syntax errors win over case
+        assert!(Color::from_hex_strict_upper("#123456").is_ok());
+        assert!(Color::from_hex_strict_lower("#123456").is_ok());
+        assert_eq!(
+            Color::from_hex_strict_upper("#ff00"),
+            Err(ColorError::InvalidHexLength { len: 4 })
+        );
+    }
+
+    #[test]
+    fn hex_batch_keeps_going_past_invalid_items() {
+        let results = Color::from_hex_batch("FF0000, 00FF00, invalid, 0000FF", ',');
+        assert_eq!(
+            results,
+            [
+                Ok(Color::from_rgb(255, 0, 0)),
+                Ok(Color::from_rgb(0, 255, 0)),
+                Err(ColorError::InvalidHexLength { len: 7 }),
+                Ok(Color::from_rgb(0, 0, 255)),
+            ]
+        );
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 3);
+        assert_eq!(Color::from_hex_batch("#FF0000\n #00FF00 \n", '\n').len(), 3);
+    }
+
+    #[test]
+    fn strict_hex_batch_returns_the_first_error() {
+        assert_eq!(
+            Color::from_hex_batch_strict("FF0000, 00FF00, invalid, 0000GG", ','),
+            Err(ColorError::InvalidHexLength { len: 7 })
+        );
+        assert_eq!(
+            Color::from_hex_batch_strict("FF0000;0000FF", ';'),
+            Ok(vec![Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255)])
+        );
+    }
+
+    // Numbers of a `Label: a, b, c` summary line with units stripped
+    fn summary_values(line: &str) -> (&str, Vec<f32>) {
+        let (label, values) = line.split_once(':').unwrap();
+        let values = values
+            .split(", ")
+            .map(|v| v.trim().trim_end_matches(['°', '%']).parse().unwrap())
+            .collect();
+        (label, values)
+    }
+
+    #[test]
+    fn all_formats_summary_round_trips_every_line() {
+        let labels = [
+            "Hex", "RGB", "HSV", "HSL", "Lab", "LCH", "Oklab", "OKLCH", "CMYK",
+        ];
+        for color in palette::web_safe_palette()
+            .into_iter()
+            .chain([Color::from_rgb(18, 52, 86), Color::from_rgb(250, 10, 130)])
+        {
+            let summary = color.to_all_formats_string();
+            let lines: Vec<&str> = summary.lines().collect();
+            assert_eq!(lines.len(), labels.len());
+            for (line, label) in lines.iter().zip(labels) {
+                // Labels are padded so the values line up
+                assert_eq!(line.find(|c: char| c.is_ascii_digit() || c == '#'), Some(7));
+                if label == "Hex" {
+                    assert_eq!(Color::from_hex(line[7..].trim()), Ok(color.clone()));
+                    continue;
+                }
+                let (parsed_label, v) = summary_values(line);
+                assert_eq!(parsed_label, label);
+                let back = match label {
+                    "RGB" => Color::from_rgb(v[0] as u8, v[1] as u8, v[2] as u8),
+                    "HSV" => Color::from_hsv(v[0], v[1] / 100.0, v[2] / 100.0),
+                    "HSL" => Color::from_hsl(v[0], v[1] / 100.0, v[2] / 100.0),
+                    "Lab" => Color::from_lab(v[0], v[1], v[2]),
+                    "LCH" => Color::from_lch(v[0], v[1], v[2]),
+                    "Oklab" => Color::from_oklab(v[0], v[1], v[2]),
+                    "OKLCH" => Color::from_oklch(v[0], v[1], v[2]),
+                    _ => Color::from_cmyk(v[0] / 100.0, v[1] / 100.0, v[2] / 100.0, v[3] / 100.0),
+                };
+                let close = back.r.abs_diff(color.r) <= 1
+                    && back.g.abs_diff(color.g) <= 1
+                    && back.b.abs_diff(color.b) <= 1;
+                assert!(close, "{line} gave {back:?} for {color:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn hsv_hue_is_not_mirrored_between_magenta_and_red() {
+        // Used to come out as 30° because the negative hue was abs()-ed
+        let (h, _, _) = Color::from_rgb(255, 0, 127).to_hsv();
+        assert!((h - 330.1).abs() < 0.1, "{h}");
+        let (h, _, _) = Color::from_rgb(255, 127, 0).to_hsv();
+        assert!((h - 29.9).abs() < 0.1, "{h}");
+        for degrees in (0..360).step_by(15) {
+            let color = Color::from_hsv(degrees as f32, 1.0, 1.0);
+            let (h, _, _) = color.to_hsv();
+            let diff = (h - degrees as f32).rem_euclid(360.0);
+            assert!(diff.min(360.0 - diff) < 0.5, "{degrees} came back as {h}");
+        }
+    }
+
+    #[test]
+    fn hsb_is_hsv_under_another_name() {
+        for h in (0..360).step_by(23) {
+            for s in [0.0, 0.25, 0.6, 1.0] {
+                for b in [0.0, 0.4, 0.85, 1.0] {
+                    let h = h as f32;
+                    assert_eq!(Color::from_hsb(h, s, b), Color::from_hsv(h, s, b));
+                }
+            }
+        }
+        for color in palette::web_safe_palette() {
+            assert_eq!(color.to_hsb(), color.to_hsv());
+        }
+    }
+
+    #[test]
+    fn grayscale_constructors() {
+        assert_eq!(
+            Color::from_grayscale(0x80),
+            Color::from_rgb(0x80, 0x80, 0x80)
+        );
+        assert_eq!(
+            Color::from_grayscale_f32(0.5),
+            Ok(Color::from_rgb(128, 128, 128))
+        );
+        assert_eq!(
+            Color::from_grayscale_f32(1.0),
+            Ok(Color::from_rgb(255, 255, 255))
+        );
+        assert_eq!(
+            Color::from_grayscale_f32(1.5),
+            Err(ColorError::OutOfRange {
+                component: "luma",
+                value: 1.5
+            })
+        );
+        assert!(Color::from_grayscale_f32(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn stable_and_short_ids_are_unique_over_web_safe_colors() {
+        let colors = palette::web_safe_palette();
+        let stable: std::collections::HashSet<String> =
+            colors.iter().map(Color::to_stable_id).collect();
+        let short: std::collections::HashSet<String> =
+            colors.iter().map(Color::to_short_id).collect();
+        assert_eq!(stable.len(), 216);
+        assert_eq!(short.len(), 216);
+        assert!(stable
+            .iter()
+            .all(|id| id.len() == 6 && id.bytes().all(|b| b.is_ascii_alphanumeric())));
+        assert!(short.iter().all(|id| id.len() >= 4
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b.is_ascii_lowercase())));
+    }
+
+    #[test]
+    fn short_ids_are_base_36() {
+        assert_eq!(Color::from_rgb(0, 0, 0).to_short_id(), "0000");
+        assert_eq!(Color::from_rgb(0, 0, 35).to_short_id(), "000z");
+        assert_eq!(Color::from_rgb(0x19, 0xA0, 0xFF).to_short_id(), "zzzz");
+        assert_eq!(Color::from_rgb(0x19, 0xA1, 0x00).to_short_id(), "10000");
+        assert_eq!(Color::from_rgb(255, 255, 255).to_short_id(), "9zldr");
+        assert_eq!(Color::from_rgb(0x12, 0x34, 0x56).to_stable_id(), "123456");
+    }
+
+    // Parameters of a single SGR escape, `ESC [ p1;p2;... m`
+    fn sgr_params(sequence: &str) -> Vec<u16> {
+        let params = sequence
+            .strip_prefix("\x1b[")
+            .and_then(|s| s.strip_suffix('m'))
+            .unwrap_or_else(|| panic!("{sequence:?} is not an SGR sequence"));
+        params.split(';').map(|p| p.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn styled_foregrounds_put_the_attribute_before_the_color() {
+        let color = Color::from_rgb(255, 128, 0);
+        for (sequence, attribute) in [
+            (color.to_ansi_bold_fg(), 1),
+            (color.to_ansi_dim_fg(), 2),
+            (color.to_ansi_italic_fg(), 3),
+            (color.to_ansi_underline_fg(), 4),
+        ] {
+            assert_eq!(sgr_params(&sequence), [attribute, 38, 2, 255, 128, 0]);
+        }
+        assert_eq!(color.to_ansi_bold_fg(), "\x1b[1;38;2;255;128;0m");
+        assert_eq!(sgr_params(Color::reset_all()), [0]);
+    }
+
+    #[test]
+    fn u8_slices_report_their_length() {
+        for short in [&[][..], &[1], &[1, 2]] {
+            let error = ColorError::SliceTooShort {
+                expected: 3,
+                got: short.len(),
+            };
+            assert_eq!(Color::from_u8_slice(short), Err(error.clone()));
+            assert_eq!(Color::from_u8_slice_bgr(short), Err(error.clone()));
+            let mut buf = short.to_vec();
+            assert_eq!(
+                Color::from_rgb(1, 2, 3).to_u8_slice(&mut buf),
+                Err(error.clone())
+            );
+            assert_eq!(
+                Color::from_rgb(1, 2, 3).to_u8_slice_bgr(&mut buf),
+                Err(error)
+            );
+            assert_eq!(buf, short);
+        }
+    }
+
+    #[test]
+    fn u8_slices_round_trip_in_both_orders() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        let mut buf = [0xAA; 5];
+        color.to_u8_slice(&mut buf).unwrap();
+        assert_eq!(buf, [0x12, 0x34, 0x56, 0xAA, 0xAA]);
+        assert_eq!(Color::from_u8_slice(&buf), Ok(color.clone()));
+        assert_eq!(
+            Color::from_u8_slice_bgr(&buf),
+            Ok(Color::from_rgb(0x56, 0x34, 0x12))
+        );
+
+        color.to_u8_slice_bgr(&mut buf).unwrap();
+        assert_eq!(buf, [0x56, 0x34, 0x12, 0xAA, 0xAA]);
+        assert_eq!(Color::from_u8_slice_bgr(&buf), Ok(color));
+    }
+
+    #[test]
+    fn wide_integer_constructors_check_or_clamp() {
+        assert_eq!(Color::checked_from_rgb_u32(300, 0, 0), None);
+        assert_eq!(Color::checked_from_rgb_u32(0, 256, 0), None);
+        assert_eq!(Color::checked_from_rgb_u32(0, 0, u32::MAX), None);
+        assert_eq!(
+            Color::checked_from_rgb_u32(255, 128, 0),
+            Some(Color::from_rgb(255, 128, 0))
+        );
+        assert_eq!(
+            Color::saturating_from_rgb_u32(300, 0, 0),
+            Color::from_rgb(255, 0, 0)
+        );
+        assert_eq!(
+            Color::saturating_from_rgb_u32(12, u32::MAX, 255),
+            Color::from_rgb(12, 255, 255)
+        );
+    }
+
+    #[test]
+    fn hex_padded_pads_on_the_left_and_never_truncates() {
+        let red = Color::from_rgb(255, 0, 0);
+        assert_eq!(red.to_hex_padded(6, '0'), "FF0000");
+        assert_eq!(red.to_hex_padded(8, '0'), "00FF0000");
+        assert_eq!(red.to_hex_padded(10, ' '), "    FF0000");
+        for shorter in [0, 2, 5] {
+            assert_eq!(red.to_hex_padded(shorter, '0'), "FF0000");
+        }
+        assert_eq!(red.to_hex_padded(8, 'F'), "FFFF0000");
+    }
 }
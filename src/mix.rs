@@ -0,0 +1,443 @@
+use crate::error::ColorError;
+use crate::Color;
+use std::str::FromStr;
+
+// Hues closer than this chroma/saturation are treated as missing, so
+// blending with a gray doesn't swing through an arbitrary hue
+const ACHROMATIC: f32 = 1e-4;
+
+// Which way round the hue circle to travel, as in CSS Color 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HueDirection {
+    #[default]
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+// The color space a blend is computed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterpolationSpace {
+    // Gamma-encoded sRGB channels
+    #[default]
+    Srgb,
+    // Linear-light sRGB, which keeps mixes from going muddy and dark
+    LinearRgb,
+    Hsl(HueDirection),
+    Hsv(HueDirection),
+    Oklab,
+    Oklch(HueDirection),
+    // CIELAB relative to D65
+    Lab,
+}
+
+impl FromStr for InterpolationSpace {
+    type Err = String;
+
+    // Accepts srgb, linear, lab, oklab, or hsl / hsv / oklch with an
+    // optional `-shorter`, `-longer`, `-increasing` or `-decreasing` suffix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let (name, direction) = match lower.split_once('-') {
+            Some((name, direction)) => {
+                let direction = match direction {
+                    "shorter" => HueDirection::Shorter,
+                    "longer" => HueDirection::Longer,
+                    "increasing" => HueDirection::Increasing,
+                    "decreasing" => HueDirection::Decreasing,
+                    _ => return Err(format!("unknown hue direction `{direction}`")),
+                };
+                (name, Some(direction))
+            }
+            None => (lower.as_str(), None),
+        };
+        let space = match name {
+            "srgb" => InterpolationSpace::Srgb,
+            "linear" | "srgb-linear" => InterpolationSpace::LinearRgb,
+            "lab" => InterpolationSpace::Lab,
+            "oklab" => InterpolationSpace::Oklab,
+            "hsl" => InterpolationSpace::Hsl(direction.unwrap_or_default()),
+            "hsv" => InterpolationSpace::Hsv(direction.unwrap_or_default()),
+            "oklch" => InterpolationSpace::Oklch(direction.unwrap_or_default()),
+            _ => return Err(format!("unknown interpolation space `{s}`")),
+        };
+        if direction.is_some() && !space.is_polar() {
+            return Err(format!("`{name}` has no hue to take a direction"));
+        }
+        Ok(space)
+    }
+}
+
+impl InterpolationSpace {
+    fn is_polar(&self) -> bool {
+        matches!(
+            self,
+            InterpolationSpace::Hsl(_) | InterpolationSpace::Hsv(_) | InterpolationSpace::Oklch(_)
+        )
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// Interpolate between two hues in degrees, resolving missing hues first
+fn lerp_hue(a: Option<f32>, b: Option<f32>, t: f32, direction: HueDirection) -> f32 {
+    let (a, b) = match (a, b) {
+        (Some(a), Some(b)) => (a, b),
+        (Some(h), None) | (None, Some(h)) => (h, h),
+        (None, None) => (0.0, 0.0),
+    };
+    let mut delta = (b - a).rem_euclid(360.0);
+    match direction {
+        HueDirection::Shorter if delta > 180.0 => delta -= 360.0,
+        HueDirection::Longer if delta > 0.0 && delta <= 180.0 => delta -= 360.0,
+        HueDirection::Decreasing if delta > 0.0 => delta -= 360.0,
+        _ => {}
+    }
+    (a + delta * t).rem_euclid(360.0)
+}
+
+fn hue_if(hue: f32, chroma: f32) -> Option<f32> {
+    (chroma > ACHROMATIC).then_some(hue)
+}
+
+impl Color {
+    // Blend from `a` (t = 0) to `b` (t = 1) in the given space. `t` is
+    // clamped, and results outside sRGB are clipped.
+    pub fn interpolate(a: &Color, b: &Color, t: f32, space: InterpolationSpace) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        match space {
+            InterpolationSpace::Srgb => {
+                let mix = |x: u8, y: u8| lerp(f32::from(x), f32::from(y), t).round() as u8;
+                Color::from_rgb(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b))
+            }
+            InterpolationSpace::LinearRgb => {
+                let (x, y) = (a.to_linear_rgb(), b.to_linear_rgb());
+                Color::from_linear_rgb([
+                    lerp(x[0], y[0], t),
+                    lerp(x[1], y[1], t),
+                    lerp(x[2], y[2], t),
+                ])
+            }
+            InterpolationSpace::Hsl(direction) => {
+                let ((h1, s1, l1), (h2, s2, l2)) = (a.to_hsl(), b.to_hsl());
+                let h = lerp_hue(hue_if(h1, s1), hue_if(h2, s2), t, direction);
+                Color::from_hsl(h, lerp(s1, s2, t), lerp(l1, l2, t))
+            }
+            InterpolationSpace::Hsv(direction) => {
+                let ((h1, s1, v1), (h2, s2, v2)) = (a.to_hsv(), b.to_hsv());
+                let h = lerp_hue(hue_if(h1, s1), hue_if(h2, s2), t, direction);
+                Color::from_hsv(h, lerp(s1, s2, t), lerp(v1, v2, t))
+            }
+            InterpolationSpace::Oklab => {
+                let ((l1, a1, b1), (l2, a2, b2)) = (a.to_oklab(), b.to_oklab());
+                Color::from_oklab(lerp(l1, l2, t), lerp(a1, a2, t), lerp(b1, b2, t))
+            }
+            InterpolationSpace::Oklch(direction) => {
+                let ((l1, c1, h1), (l2, c2, h2)) = (a.to_oklch(), b.to_oklch());
+                let h = lerp_hue(hue_if(h1, c1), hue_if(h2, c2), t, direction);
+                Color::from_oklch(lerp(l1, l2, t), lerp(c1, c2, t), h)
+            }
+            InterpolationSpace::Lab => {
+                let ((l1, a1, b1), (l2, a2, b2)) = (a.to_lab(), b.to_lab());
+                Color::from_lab(lerp(l1, l2, t), lerp(a1, a2, t), lerp(b1, b2, t))
+            }
+        }
+    }
+}
+
+// The space `Color::weighted_average` takes its mean in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AverageSpace {
+    // Linear-light sRGB, which matches how light (and screens) add up
+    #[default]
+    LinearRgb,
+    // CIELAB relative to D65, for a perceptually even mean
+    Lab,
+}
+
+// Parse `COLOR[:WEIGHT]`, e.g. `#ff0000:2`. The weight defaults to 1.
+pub fn parse_weighted_color(s: &str) -> Result<(Color, f32), String> {
+    let (color, weight) = match s.rsplit_once(':') {
+        Some((color, weight)) => {
+            let weight: f32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight `{weight}`"))?;
+            (color, weight)
+        }
+        None => (s, 1.0),
+    };
+    let color = color.parse::<Color>().map_err(|e| e.to_string())?;
+    Ok((color, weight))
+}
+
+impl Color {
+    #[allow(dead_code)]
+    // Weighted mean of the colors in linear light. See
+    // `weighted_average_in`.
+    pub fn weighted_average(colors: &[(Color, f32)]) -> Result<Color, ColorError> {
+        Self::weighted_average_in(colors, AverageSpace::LinearRgb)
+    }
+
+    // Weighted mean of the colors in the given space. Weights are
+    // normalized, so they need not sum to 1, but each must be non-negative
+    // and their total positive. A single color comes back unchanged, and
+    // the result doesn't depend on the order of `colors`.
+    pub fn weighted_average_in(
+        colors: &[(Color, f32)],
+        space: AverageSpace,
+    ) -> Result<Color, ColorError> {
+        if let Some(&(_, weight)) = colors.iter().find(|(_, w)| !(w.is_finite() && *w >= 0.0)) {
+            return Err(ColorError::InvalidWeight(weight));
+        }
+        let total: f32 = colors.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            return Err(ColorError::InvalidWeight(total));
+        }
+        if let [(color, _)] = colors {
+            return Ok(color.clone());
+        }
+
+        // Float addition isn't associative, so sum in a canonical order
+        let mut sorted: Vec<&(Color, f32)> = colors.iter().collect();
+        sorted.sort_by(|(a, wa), (b, wb)| {
+            (a.r, a.g, a.b).cmp(&(b.r, b.g, b.b)).then(wa.total_cmp(wb))
+        });
+        let mut sum = [0.0f32; 3];
+        for (color, weight) in sorted {
+            let components = match space {
+                AverageSpace::LinearRgb => color.to_linear_rgb(),
+                AverageSpace::Lab => {
+                    let (l, a, b) = color.to_lab();
+                    [l, a, b]
+                }
+            };
+            for (s, c) in sum.iter_mut().zip(components) {
+                *s += c * weight / total;
+            }
+        }
+        Ok(match space {
+            AverageSpace::LinearRgb => Color::from_linear_rgb(sum),
+            AverageSpace::Lab => Color::from_lab(sum[0], sum[1], sum[2]),
+        })
+    }
+
+    #[allow(dead_code)]
+    // Mix colors like paint by proportion, in linear light. Weights are
+    // normalized internally. `None` if there are no colors or the weights
+    // are not usable (see `weighted_average_in`).
+    pub fn mix_weighted(colors: &[(&Color, f32)]) -> Option<Color> {
+        let owned: Vec<(Color, f32)> = colors.iter().map(|&(c, w)| (c.clone(), w)).collect();
+        Self::weighted_average(&owned).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_SPACES: [InterpolationSpace; 7] = [
+        InterpolationSpace::Srgb,
+        InterpolationSpace::LinearRgb,
+        InterpolationSpace::Hsl(HueDirection::Shorter),
+        InterpolationSpace::Hsv(HueDirection::Shorter),
+        InterpolationSpace::Oklab,
+        InterpolationSpace::Oklch(HueDirection::Shorter),
+        InterpolationSpace::Lab,
+    ];
+
+    fn assert_close(got: &Color, expected: &Color) {
+        let close = got.r.abs_diff(expected.r) <= 1
+            && got.g.abs_diff(expected.g) <= 1
+            && got.b.abs_diff(expected.b) <= 1;
+        assert!(close, "{got:?} != {expected:?}");
+    }
+
+    #[test]
+    fn every_space_keeps_the_endpoints() {
+        let (a, b) = (Color::from_rgb(220, 40, 60), Color::from_rgb(30, 90, 200));
+        for space in ALL_SPACES {
+            assert_close(&Color::interpolate(&a, &b, 0.0, space), &a);
+            assert_close(&Color::interpolate(&a, &b, 1.0, space), &b);
+            // t is clamped
+            assert_close(&Color::interpolate(&a, &b, 2.0, space), &b);
+        }
+    }
+
+    #[test]
+    fn srgb_matches_a_per_channel_lerp() {
+        let (a, b) = (Color::from_rgb(220, 40, 60), Color::from_rgb(30, 90, 200));
+        for t in [0.1, 0.25, 0.5, 0.9] {
+            let channel = |x: u8, y: u8| lerp(f32::from(x), f32::from(y), t).round() as u8;
+            let expected = Color::from_rgb(channel(a.r, b.r), channel(a.g, b.g), channel(a.b, b.b));
+            assert_eq!(
+                Color::interpolate(&a, &b, t, InterpolationSpace::Srgb),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn oklab_and_oklch_match_component_mixes() {
+        let (a, b) = (Color::from_rgb(220, 40, 60), Color::from_rgb(30, 90, 200));
+        let ((l1, a1, b1), (l2, a2, b2)) = (a.to_oklab(), b.to_oklab());
+        let ((cl1, c1, h1), (cl2, c2, h2)) = (a.to_oklch(), b.to_oklch());
+        for t in [0.25, 0.5, 0.75] {
+            assert_eq!(
+                Color::interpolate(&a, &b, t, InterpolationSpace::Oklab),
+                Color::from_oklab(lerp(l1, l2, t), lerp(a1, a2, t), lerp(b1, b2, t))
+            );
+            assert_eq!(
+                Color::interpolate(&a, &b, t, InterpolationSpace::Oklch(HueDirection::Shorter)),
+                Color::from_oklch(
+                    lerp(cl1, cl2, t),
+                    lerp(c1, c2, t),
+                    lerp_hue(Some(h1), Some(h2), t, HueDirection::Shorter)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn hue_direction_picks_the_way_round() {
+        assert_eq!(
+            lerp_hue(Some(350.0), Some(10.0), 0.5, HueDirection::Shorter),
+            0.0
+        );
+        assert_eq!(
+            lerp_hue(Some(350.0), Some(10.0), 0.5, HueDirection::Longer),
+            180.0
+        );
+        assert_eq!(
+            lerp_hue(Some(10.0), Some(350.0), 0.5, HueDirection::Increasing),
+            180.0
+        );
+        assert_eq!(
+            lerp_hue(Some(10.0), Some(350.0), 0.5, HueDirection::Decreasing),
+            0.0
+        );
+        // A gray end takes the other end's hue
+        assert_eq!(
+            lerp_hue(None, Some(120.0), 0.5, HueDirection::Shorter),
+            120.0
+        );
+
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        let hsl =
+            |direction| Color::interpolate(&red, &blue, 0.5, InterpolationSpace::Hsl(direction));
+        assert_eq!(hsl(HueDirection::Shorter), Color::from_rgb(255, 0, 255));
+        assert_eq!(hsl(HueDirection::Longer), Color::from_rgb(0, 255, 0));
+    }
+
+    #[test]
+    fn weighted_average_mixes_in_linear_light() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        let mean = Color::weighted_average(&[(red.clone(), 2.0), (blue.clone(), 1.0)]).unwrap();
+        // Linear 2/3 and 1/3, sRGB-encoded by hand
+        assert_eq!(mean, Color::from_rgb(213, 0, 156));
+        // The naive byte average is much darker
+        assert_ne!(mean, Color::from_rgb(170, 0, 85));
+        // Equal weights agree with a linear-light midpoint
+        assert_eq!(
+            Color::weighted_average(&[(red.clone(), 1.0), (blue.clone(), 1.0)]).unwrap(),
+            Color::interpolate(&red, &blue, 0.5, InterpolationSpace::LinearRgb)
+        );
+    }
+
+    #[test]
+    fn weighted_average_in_lab_agrees_with_interpolate() {
+        let (a, b) = (Color::from_rgb(220, 40, 60), Color::from_rgb(30, 90, 200));
+        let mean =
+            Color::weighted_average_in(&[(a.clone(), 2.0), (b.clone(), 1.0)], AverageSpace::Lab)
+                .unwrap();
+        assert_close(
+            &mean,
+            &Color::interpolate(&a, &b, 1.0 / 3.0, InterpolationSpace::Lab),
+        );
+    }
+
+    #[test]
+    fn weighted_average_edge_cases() {
+        let color = Color::from_rgb(12, 34, 56);
+        assert_eq!(
+            Color::weighted_average(&[(color.clone(), 0.3)]),
+            Ok(color.clone())
+        );
+        assert_eq!(
+            Color::weighted_average(&[]),
+            Err(ColorError::InvalidWeight(0.0))
+        );
+        assert_eq!(
+            Color::weighted_average(&[(color.clone(), 0.0)]),
+            Err(ColorError::InvalidWeight(0.0))
+        );
+        assert_eq!(
+            Color::weighted_average(&[(color.clone(), 1.0), (color, -1.0)]),
+            Err(ColorError::InvalidWeight(-1.0))
+        );
+
+        let colors = [
+            (Color::from_rgb(255, 0, 0), 1.0),
+            (Color::from_rgb(0, 255, 0), 2.5),
+            (Color::from_rgb(0, 0, 255), 0.5),
+        ];
+        let mut reversed = colors.clone();
+        reversed.reverse();
+        assert_eq!(
+            Color::weighted_average(&colors),
+            Color::weighted_average(&reversed)
+        );
+    }
+
+    #[test]
+    fn parses_weighted_colors() {
+        assert_eq!(
+            parse_weighted_color("#ff0000:2"),
+            Ok((Color::from_rgb(255, 0, 0), 2.0))
+        );
+        assert_eq!(
+            parse_weighted_color("#0000ff"),
+            Ok((Color::from_rgb(0, 0, 255), 1.0))
+        );
+        assert!(parse_weighted_color("#0000ff:heavy").is_err());
+    }
+
+    #[test]
+    fn mix_weighted_halves_match_a_linear_mix() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        let purple = Color::mix_weighted(&[(&red, 50.0), (&blue, 50.0)]).unwrap();
+        assert_close(
+            &purple,
+            &Color::interpolate(&red, &blue, 0.5, InterpolationSpace::LinearRgb),
+        );
+        assert_eq!(
+            Color::mix_weighted(&[(&blue, 0.5), (&red, 0.5)]),
+            Some(purple)
+        );
+    }
+
+    #[test]
+    fn mix_weighted_of_one_color_is_that_color() {
+        let ochre = Color::from_rgb(204, 119, 34);
+        assert_eq!(Color::mix_weighted(&[(&ochre, 100.0)]), Some(ochre.clone()));
+        assert_eq!(Color::mix_weighted(&[(&ochre, 0.2)]), Some(ochre));
+        assert_eq!(Color::mix_weighted(&[]), None);
+    }
+
+    #[test]
+    fn mix_weighted_ignores_order() {
+        let red = Color::from_rgb(227, 0, 34);
+        let white = Color::from_rgb(255, 255, 255);
+        let ochre = Color::from_rgb(204, 119, 34);
+        let paint = [(&red, 50.0), (&white, 30.0), (&ochre, 20.0)];
+        let mixed = Color::mix_weighted(&paint);
+        assert!(mixed.is_some());
+        for order in [[0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]] {
+            let permuted = order.map(|i| paint[i]);
+            assert_eq!(Color::mix_weighted(&permuted), mixed);
+        }
+    }
+}
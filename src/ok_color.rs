@@ -0,0 +1,418 @@
+// Okhsl and Okhsv, the picker spaces Björn Ottosson built on Oklab
+// (https://bottosson.github.io/posts/colorpicker/). Hues are in degrees and
+// match the Oklab hue angle; the other components are in [0, 1].
+
+use crate::space::{linear_srgb_to_oklab, oklab_to_linear_srgb};
+use crate::Color;
+
+// Below this Oklab chroma a color counts as gray and its hue as 0
+const ACHROMATIC_CHROMA: f32 = 1e-6;
+
+// Perceptual lightness estimate used in place of Oklab L, closer to CIE L*
+// in the darks
+fn toe(x: f32) -> f32 {
+    const K1: f32 = 0.206;
+    const K2: f32 = 0.03;
+    const K3: f32 = (1.0 + K1) / (1.0 + K2);
+    0.5 * (K3 * x - K1 + ((K3 * x - K1) * (K3 * x - K1) + 4.0 * K2 * K3 * x).sqrt())
+}
+
+fn toe_inv(x: f32) -> f32 {
+    const K1: f32 = 0.206;
+    const K2: f32 = 0.03;
+    const K3: f32 = (1.0 + K1) / (1.0 + K2);
+    (x * x + K1 * x) / (K3 * (x + K2))
+}
+
+// LMS' coefficients of a unit Oklab hue direction (a, b)
+fn lms_direction(a: f32, b: f32) -> [f32; 3] {
+    [
+        0.396_337_78 * a + 0.215_803_76 * b,
+        -0.105_561_346 * a - 0.063_854_17 * b,
+        -0.089_484_18 * a - 1.291_485_5 * b,
+    ]
+}
+
+// Rows of the LMS to linear sRGB matrix
+const LMS_TO_RGB: [[f32; 3]; 3] = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+// Largest saturation C/L at full lightness before one sRGB channel clips,
+// for the unit hue direction (a, b): a polynomial fit refined by one
+// Halley step
+fn compute_max_saturation(a: f32, b: f32) -> f32 {
+    // Pure blue sits on the corner where all three regions meet and only the
+    // green fit is accurate there, so rounding must not push it elsewhere
+    const CORNER_TOLERANCE: f32 = 1e-5;
+    let (k, w) = if -1.881_703_3 * a - 0.809_364_9 * b > 1.0 + CORNER_TOLERANCE {
+        // Red clips first
+        (
+            [
+                1.190_862_8,
+                1.765_767_3,
+                0.596_626_4,
+                0.755_152,
+                0.567_712_4,
+            ],
+            LMS_TO_RGB[0],
+        )
+    } else if 1.814_441 * a - 1.194_452_8 * b > 1.0 - CORNER_TOLERANCE {
+        // Green clips first
+        (
+            [
+                0.739_565_15,
+                -0.459_544_04,
+                0.082_854_27,
+                0.125_410_7,
+                0.145_032_04,
+            ],
+            LMS_TO_RGB[1],
+        )
+    } else {
+        // Blue clips first
+        (
+            [
+                1.357_336_5,
+                -0.009_157_99,
+                -1.151_302_1,
+                -0.505_596_06,
+                0.006_921_67,
+            ],
+            LMS_TO_RGB[2],
+        )
+    };
+
+    let saturation = k[0] + k[1] * a + k[2] * b + k[3] * a * a + k[4] * a * b;
+    let kd = lms_direction(a, b);
+    let mut f = [0.0; 3];
+    for i in 0..3 {
+        let c = 1.0 + saturation * kd[i];
+        let (value, d1, d2) = (c * c * c, 3.0 * kd[i] * c * c, 6.0 * kd[i] * kd[i] * c);
+        f[0] += w[i] * value;
+        f[1] += w[i] * d1;
+        f[2] += w[i] * d2;
+    }
+    saturation - f[0] * f[1] / (f[1] * f[1] - 0.5 * f[0] * f[2])
+}
+
+// Lightness and chroma of the most saturated sRGB color along a hue
+fn find_cusp(a: f32, b: f32) -> (f32, f32) {
+    let s_cusp = compute_max_saturation(a, b);
+    let [r, g, bl] = oklab_to_linear_srgb([1.0, s_cusp * a, s_cusp * b]);
+    let l_cusp = (1.0 / r.max(g).max(bl)).cbrt();
+    (l_cusp, l_cusp * s_cusp)
+}
+
+// How far along the line from (L0, 0) to (L1, C1) the sRGB gamut boundary
+// lies, for the unit hue direction (a, b)
+fn find_gamut_intersection(a: f32, b: f32, l1: f32, c1: f32, l0: f32, cusp: (f32, f32)) -> f32 {
+    let (cusp_l, cusp_c) = cusp;
+    if (l1 - l0) * cusp_c - (cusp_l - l0) * c1 <= 0.0 {
+        // Below the cusp the boundary is a straight line to black
+        return cusp_c * l0 / (c1 * cusp_l + cusp_c * (l0 - l1));
+    }
+
+    // Above it, start from the straight line to white and take one Halley
+    // step against each channel's upper limit
+    let mut t = cusp_c * (l0 - 1.0) / (c1 * (cusp_l - 1.0) + cusp_c * (l0 - l1));
+    let kd = lms_direction(a, b);
+    let (dl, dc) = (l1 - l0, c1);
+    let l = l0 * (1.0 - t) + t * l1;
+    let c = t * c1;
+
+    let mut lms = [[0.0; 3]; 3];
+    for i in 0..3 {
+        let x = l + c * kd[i];
+        let dt = dl + dc * kd[i];
+        lms[i] = [x * x * x, 3.0 * dt * x * x, 6.0 * dt * dt * x];
+    }
+    let step = LMS_TO_RGB
+        .iter()
+        .map(|w| {
+            let channel = |order: usize| (0..3).map(|i| w[i] * lms[i][order]).sum::<f32>();
+            let (value, d1, d2) = (channel(0) - 1.0, channel(1), channel(2));
+            let u = d1 / (d1 * d1 - 0.5 * value * d2);
+            if u >= 0.0 {
+                -value * u
+            } else {
+                f32::MAX
+            }
+        })
+        .fold(f32::MAX, f32::min);
+    t += step;
+    t
+}
+
+// Cusp expressed as the slopes S = C/L and T = C/(1 - L) of the two gamut
+// edges
+fn to_st((l, c): (f32, f32)) -> (f32, f32) {
+    (c / l, c / (1.0 - l))
+}
+
+// Fitted S and T giving a smooth, hue-independent mid saturation
+fn get_st_mid(a: f32, b: f32) -> (f32, f32) {
+    let s = 0.115_169_93
+        + 1.0
+            / (7.447_789_7
+                + 4.159_012_4 * b
+                + a * (-2.195_573_5
+                    + 1.751_984 * b
+                    + a * (-2.137_049_5 - 10.023_01 * b
+                        + a * (-4.248_945_6 + 5.387_708 * b + 4.698_91 * a))));
+    let t = 0.112_396_42
+        + 1.0
+            / (1.613_203_2 - 0.681_243_8 * b
+                + a * (0.403_706_12
+                    + 0.901_481_2 * b
+                    + a * (-0.270_879_43
+                        + 0.612_239_9 * b
+                        + a * (0.002_992_15 - 0.453_995_68 * b - 0.146_618_72 * a))));
+    (s, t)
+}
+
+// The three chroma anchors at Okhsl saturation 0→0.8 (C0 and Cmid) and 1
+// (Cmax) for Oklab lightness `l` along a hue
+fn get_cs(l: f32, a: f32, b: f32) -> (f32, f32, f32) {
+    let cusp = find_cusp(a, b);
+    let c_max = find_gamut_intersection(a, b, l, 1.0, l, cusp);
+    let (s_max, t_max) = to_st(cusp);
+    let k = c_max / (l * s_max).min((1.0 - l) * t_max);
+
+    let (s_mid, t_mid) = get_st_mid(a, b);
+    let (ca, cb) = (l * s_mid, (1.0 - l) * t_mid);
+    let c_mid = 0.9 * k * (1.0 / (1.0 / ca.powi(4) + 1.0 / cb.powi(4))).sqrt().sqrt();
+
+    let (ca, cb) = (l * 0.4, (1.0 - l) * 0.8);
+    let c_0 = (1.0 / (1.0 / (ca * ca) + 1.0 / (cb * cb))).sqrt();
+    (c_0, c_mid, c_max)
+}
+
+// Saturation split point of the Okhsl chroma curve
+const MID: f32 = 0.8;
+
+impl Color {
+    #[allow(dead_code)]
+    // Constructor from Okhsl, hue in degrees and saturation and lightness
+    // in [0, 1]
+    pub fn from_okhsl(h: f32, s: f32, l: f32) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        if l >= 1.0 {
+            return Color::from_rgb(255, 255, 255);
+        }
+        if l <= 0.0 {
+            return Color::from_rgb(0, 0, 0);
+        }
+
+        let (b, a) = h.to_radians().sin_cos();
+        let lightness = toe_inv(l);
+        let (c_0, c_mid, c_max) = get_cs(lightness, a, b);
+        let chroma = if s < MID {
+            let t = s / MID;
+            let k_1 = MID * c_0;
+            let k_2 = 1.0 - k_1 / c_mid;
+            t * k_1 / (1.0 - k_2 * t)
+        } else {
+            let t = (s - MID) / (1.0 - MID);
+            let k_1 = (1.0 - MID) * c_mid * c_mid / (MID * MID * c_0);
+            let k_2 = 1.0 - k_1 / (c_max - c_mid);
+            c_mid + t * k_1 / (1.0 - k_2 * t)
+        };
+        Color::from_linear_rgb(oklab_to_linear_srgb([lightness, chroma * a, chroma * b]))
+    }
+
+    #[allow(dead_code)]
+    // Convert to Okhsl, see `from_okhsl`. Grays get a hue of 0.
+    pub fn to_okhsl(&self) -> (f32, f32, f32) {
+        let [lightness, a, b] = linear_srgb_to_oklab(self.to_linear_rgb());
+        let chroma = a.hypot(b);
+        let l = toe(lightness).clamp(0.0, 1.0);
+        if chroma < ACHROMATIC_CHROMA || l <= 0.0 || l >= 1.0 {
+            return (0.0, 0.0, l);
+        }
+
+        let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+        let (c_0, c_mid, c_max) = get_cs(lightness, a / chroma, b / chroma);
+        let s = if chroma < c_mid {
+            let k_1 = MID * c_0;
+            let k_2 = 1.0 - k_1 / c_mid;
+            MID * chroma / (k_1 + k_2 * chroma)
+        } else {
+            let k_1 = (1.0 - MID) * c_mid * c_mid / (MID * MID * c_0);
+            let k_2 = 1.0 - k_1 / (c_max - c_mid);
+            let t = (chroma - c_mid) / (k_1 + k_2 * (chroma - c_mid));
+            MID + (1.0 - MID) * t
+        };
+        (h, s.clamp(0.0, 1.0), l)
+    }
+}
+
+// Okhsv saturation at which the chroma curve meets the cusp triangle's
+// lower edge
+const S_0: f32 = 0.5;
+
+// Factor that brings Oklab (L, C) onto the sRGB gamut boundary, found from
+// where its toe-corrected counterpart (L_vt, C_vt) lands
+fn value_scale(l_vt: f32, c_vt: f32, a: f32, b: f32) -> f32 {
+    let [r, g, bl] = oklab_to_linear_srgb([l_vt, a * c_vt, b * c_vt]);
+    (1.0 / r.max(g).max(bl).max(0.0)).cbrt()
+}
+
+impl Color {
+    #[allow(dead_code)]
+    // Constructor from Okhsv, hue in degrees and saturation and value in
+    // [0, 1]
+    pub fn from_okhsv(h: f32, s: f32, v: f32) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        if v <= 0.0 {
+            return Color::from_rgb(0, 0, 0);
+        }
+
+        let (b, a) = h.to_radians().sin_cos();
+        let (s_max, t_max) = to_st(find_cusp(a, b));
+        let k = 1.0 - S_0 / s_max;
+
+        // Lightness and chroma at full value on the triangle's outer edge
+        let denominator = S_0 + t_max - t_max * k * s;
+        let l_v = 1.0 - s * S_0 / denominator;
+        let c_v = s * t_max * S_0 / denominator;
+
+        let l_vt = toe_inv(l_v);
+        let c_vt = c_v * l_vt / l_v;
+        let lightness = toe_inv(v * l_v);
+        let chroma = v * c_v * lightness / (v * l_v);
+
+        let scale = value_scale(l_vt, c_vt, a, b);
+        let (lightness, chroma) = (lightness * scale, chroma * scale);
+        Color::from_linear_rgb(oklab_to_linear_srgb([lightness, chroma * a, chroma * b]))
+    }
+
+    #[allow(dead_code)]
+    // Convert to Okhsv, see `from_okhsv`. Grays get a hue of 0.
+    pub fn to_okhsv(&self) -> (f32, f32, f32) {
+        let [lightness, a, b] = linear_srgb_to_oklab(self.to_linear_rgb());
+        let chroma = a.hypot(b);
+        if chroma < ACHROMATIC_CHROMA || lightness <= 0.0 {
+            return (0.0, 0.0, toe(lightness).clamp(0.0, 1.0));
+        }
+
+        let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+        let (a, b) = (a / chroma, b / chroma);
+        let (s_max, t_max) = to_st(find_cusp(a, b));
+        let k = 1.0 - S_0 / s_max;
+
+        // Project onto the triangle edge along the line from black
+        let t = t_max / (chroma + lightness * t_max);
+        let (l_v, c_v) = (t * lightness, t * chroma);
+        let l_vt = toe_inv(l_v);
+        let c_vt = c_v * l_vt / l_v;
+
+        let scale = value_scale(l_vt, c_vt, a, b);
+        let lightness = lightness / scale;
+        let v = toe(lightness) / l_v;
+        let s = (S_0 + t_max) * c_v / (t_max * S_0 + t_max * k * c_v);
+        (h, s.clamp(0.0, 1.0), v.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::web_safe_palette;
+
+    fn max_channel_error(a: &Color, b: &Color) -> u8 {
+        a.r.abs_diff(b.r)
+            .max(a.g.abs_diff(b.g))
+            .max(a.b.abs_diff(b.b))
+    }
+
+    #[test]
+    fn okhsl_of_primaries_and_grays() {
+        let (h, s, l) = Color::from_rgb(255, 0, 0).to_okhsl();
+        assert!((h - 29.23).abs() < 0.01, "red hue {h}");
+        assert!((s - 1.0).abs() < 1e-4, "red saturation {s}");
+        assert!((l - 0.568).abs() < 1e-3, "red lightness {l}");
+
+        let (_, s, l) = Color::from_rgb(255, 255, 255).to_okhsl();
+        assert_eq!((s, l), (0.0, 1.0));
+        let (_, s, l) = Color::from_rgb(0, 0, 0).to_okhsl();
+        assert_eq!((s, l), (0.0, 0.0));
+        let (_, s, _) = Color::from_rgb(128, 128, 128).to_okhsl();
+        assert_eq!(s, 0.0);
+
+        assert_eq!(
+            Color::from_okhsl(123.0, 0.7, 1.0),
+            Color::from_rgb(255, 255, 255)
+        );
+        assert_eq!(Color::from_okhsl(123.0, 0.7, 0.0), Color::from_rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn okhsl_round_trips_web_safe_colors() {
+        for color in web_safe_palette() {
+            let (h, s, l) = color.to_okhsl();
+            let back = Color::from_okhsl(h, s, l);
+            assert!(
+                max_channel_error(&color, &back) <= 1,
+                "{color:?} -> {back:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn okhsl_handles_the_blue_gamut_corner() {
+        // Pure blue sits where the three cusp regions meet; rounding once
+        // sent dark blues through the red fit and lost a lot of chroma
+        for level in [51, 102, 153, 204, 255] {
+            let blue = Color::from_rgb(0, 0, level);
+            let (h, s, l) = blue.to_okhsl();
+            assert!((h - 264.05).abs() < 0.01, "{blue:?} hue {h}");
+            assert!(s > 0.99, "{blue:?} saturation {s}");
+            let back = Color::from_okhsl(h, s, l);
+            assert!(max_channel_error(&blue, &back) <= 1, "{blue:?} -> {back:?}");
+        }
+    }
+
+    #[test]
+    fn okhsv_of_primaries_and_grays() {
+        let blue = Color::from_rgb(0, 0, 255);
+        let (h, s, v) = blue.to_okhsv();
+        assert!((h - 264.05).abs() < 0.01, "blue hue {h}");
+        let (_, a, b) = blue.to_oklab();
+        let oklab_hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+        assert!((h - oklab_hue).abs() < 1e-3, "{h} vs Oklab {oklab_hue}");
+        assert!(s > 0.999 && v > 0.999, "blue ({s}, {v})");
+
+        let (_, s, v) = Color::from_rgb(255, 0, 0).to_okhsv();
+        assert!(s > 0.999 && v > 0.999, "red ({s}, {v})");
+
+        let (_, s, v) = Color::from_rgb(255, 255, 255).to_okhsv();
+        assert_eq!((s, v), (0.0, 1.0));
+        let (_, s, v) = Color::from_rgb(0, 0, 0).to_okhsv();
+        assert_eq!((s, v), (0.0, 0.0));
+
+        assert_eq!(Color::from_okhsv(200.0, 0.5, 0.0), Color::from_rgb(0, 0, 0));
+        assert_eq!(
+            Color::from_okhsv(200.0, 0.0, 1.0),
+            Color::from_rgb(255, 255, 255)
+        );
+    }
+
+    #[test]
+    fn okhsv_round_trips_web_safe_colors() {
+        let mut off_by_one = Vec::new();
+        for color in web_safe_palette() {
+            let (h, s, v) = color.to_okhsv();
+            let back = Color::from_okhsv(h, s, v);
+            let error = max_channel_error(&color, &back);
+            assert!(error <= 1, "{color:?} -> {back:?}");
+            if error == 1 {
+                off_by_one.push(color.to_hex());
+            }
+        }
+        // The saturated blues and violets that land a level off today
+        assert_eq!(off_by_one, ["#0033CC", "#6600CC", "#9900FF", "#CC00FF"]);
+    }
+}
@@ -0,0 +1,1155 @@
+use crate::error::ColorParseError;
+use crate::json::{self, Json};
+use crate::space;
+use crate::xml::{self, Token};
+use crate::Color;
+
+// Build an Inkscape palette XML document
+pub fn palette_to_inkscape_xml(name: &str, palette: &[(&str, Color)]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!("<palette name=\"{}\">\n", xml::escape(name)));
+    for (color_name, color) in palette {
+        out.push_str(&format!(
+            "  <color name=\"{}\" r=\"{}\" g=\"{}\" b=\"{}\"/>\n",
+            xml::escape(color_name),
+            color.r,
+            color.g,
+            color.b
+        ));
+    }
+    out.push_str("</palette>\n");
+    out
+}
+
+#[allow(dead_code)]
+// Read the named colors back out of an Inkscape palette XML document
+pub fn parse_inkscape_palette_xml(xml: &str) -> Result<Vec<(String, Color)>, ColorParseError> {
+    let tokens = xml::tokenize(xml).map_err(ColorParseError::Malformed)?;
+
+    let mut in_palette = false;
+    let mut seen_palette = false;
+    let mut colors = Vec::new();
+    for token in &tokens {
+        match token {
+            Token::Open {
+                name: "palette", ..
+            } => {
+                if seen_palette {
+                    return Err(ColorParseError::Malformed(
+                        "more than one <palette> element".to_string(),
+                    ));
+                }
+                seen_palette = true;
+                in_palette = true;
+            }
+            Token::Close("palette") => in_palette = false,
+            Token::Open { name: "color", .. } if in_palette => {
+                let index = colors.len();
+                let channel = |key: &str| -> Result<u8, ColorParseError> {
+                    let value = token
+                        .attr(key)
+                        .ok_or_else(|| ColorParseError::InvalidEntry {
+                            index,
+                            reason: format!("missing `{key}` attribute"),
+                        })?;
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ColorParseError::InvalidEntry {
+                            index,
+                            reason: format!("`{key}` must be 0-255, got `{value}`"),
+                        })
+                };
+                let color = Color::from_rgb(channel("r")?, channel("g")?, channel("b")?);
+                let name = token.attr("name").unwrap_or_default().to_string();
+                colors.push((name, color));
+            }
+            _ => {}
+        }
+    }
+
+    if !seen_palette {
+        return Err(ColorParseError::Malformed(
+            "no <palette> element found".to_string(),
+        ));
+    }
+    Ok(colors)
+}
+
+// Build an iTerm2 color scheme (`.itermcolors` property list) with one
+// entry per named color, e.g. `Ansi 0 Color` or `Background Color`. The
+// scheme name only appears in a comment; iTerm2 names schemes after the file.
+pub fn generate_iterm2_scheme(name: &str, colors: &[(&str, Color)]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+         \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    );
+    out.push_str(&format!(
+        "<!-- {} -->\n",
+        xml::escape(name).replace("--", "- -")
+    ));
+    out.push_str("<plist version=\"1.0\">\n<dict>\n");
+    for (color_name, color) in colors {
+        let component = |value: u8| f32::from(value) / 255.0;
+        out.push_str(&format!(
+            "\t<key>{}</key>\n\t<dict>\n\
+             \t\t<key>Alpha Component</key>\n\t\t<real>1</real>\n\
+             \t\t<key>Blue Component</key>\n\t\t<real>{}</real>\n\
+             \t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n\
+             \t\t<key>Green Component</key>\n\t\t<real>{}</real>\n\
+             \t\t<key>Red Component</key>\n\t\t<real>{}</real>\n\
+             \t</dict>\n",
+            xml::escape(color_name),
+            component(color.b),
+            component(color.g),
+            component(color.r)
+        ));
+    }
+    out.push_str("</dict>\n</plist>\n");
+    out
+}
+
+// Turn the `<key>`/value pairs of one iTerm2 color dictionary into a color.
+// Components in the `P3` color space are converted to sRGB; anything else
+// (`sRGB`, `Calibrated`, or no space at all) is read as sRGB.
+fn iterm2_color(index: usize, entries: &[(String, String)]) -> Result<Color, ColorParseError> {
+    let value = |key: &str| {
+        entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim())
+    };
+    let component = |key: &str| -> Result<f32, ColorParseError> {
+        let text = value(key).ok_or_else(|| ColorParseError::InvalidEntry {
+            index,
+            reason: format!("missing `{key}`"),
+        })?;
+        text.parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| ColorParseError::InvalidEntry {
+                index,
+                reason: format!("`{key}` must be a number, got `{text}`"),
+            })
+    };
+    let rgb = [
+        component("Red Component")?,
+        component("Green Component")?,
+        component("Blue Component")?,
+    ];
+    if value("Color Space") == Some("P3") {
+        return Ok(Color::from_linear_rgb(space::display_p3_to_linear_srgb(
+            rgb,
+        )));
+    }
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Ok(Color::from_rgb(
+        channel(rgb[0]),
+        channel(rgb[1]),
+        channel(rgb[2]),
+    ))
+}
+
+#[allow(dead_code)]
+// Read the named colors out of an iTerm2 color scheme property list. Top
+// level values that are not color dictionaries are skipped.
+pub fn parse_iterm2_scheme(xml: &str) -> Result<Vec<(String, Color)>, ColorParseError> {
+    let tokens = xml::tokenize(xml).map_err(ColorParseError::Malformed)?;
+
+    let mut depth = 0;
+    let mut seen_dict = false;
+    let mut in_key = false;
+    let mut top_key = None;
+    let mut entry_key = None;
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut colors = Vec::new();
+    for token in &tokens {
+        match token {
+            Token::Open {
+                name: "dict",
+                self_closing: false,
+                ..
+            } => {
+                depth += 1;
+                seen_dict = true;
+                entries.clear();
+            }
+            Token::Close("dict") => {
+                if depth == 2 && entries.iter().any(|(k, _)| k.ends_with(" Component")) {
+                    let color = iterm2_color(colors.len(), &entries)?;
+                    colors.push((top_key.take().unwrap_or_default(), color));
+                }
+                depth -= 1;
+            }
+            Token::Open { name: "key", .. } => in_key = true,
+            Token::Close("key") => in_key = false,
+            Token::Text(text) if in_key && depth == 1 => top_key = Some(text.clone()),
+            Token::Text(text) if in_key && depth == 2 => entry_key = Some(text.clone()),
+            Token::Text(text) if depth == 2 => {
+                if let Some(key) = entry_key.take() {
+                    entries.push((key, text.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !seen_dict {
+        return Err(ColorParseError::Malformed(
+            "no <dict> element found".to_string(),
+        ));
+    }
+    Ok(colors)
+}
+
+// Build a GIMP gradient (`.ggr`) blending through the colors with equally
+// wide linear RGB segments, one between each pair of neighbors. A single
+// color gives one flat segment.
+pub fn palette_to_gimp_gradient(name: &str, colors: &[Color]) -> String {
+    let pairs: Vec<(&Color, &Color)> = match colors {
+        [only] => vec![(only, only)],
+        _ => colors.windows(2).map(|pair| (&pair[0], &pair[1])).collect(),
+    };
+    let mut out = format!(
+        "GIMP Gradient\nName: {}\n{}\n",
+        name.replace('\n', " "),
+        pairs.len()
+    );
+    let unit = |c: u8| f32::from(c) / 255.0;
+    for (i, (left, right)) in pairs.iter().enumerate() {
+        let start = i as f64 / pairs.len() as f64;
+        let end = (i + 1) as f64 / pairs.len() as f64;
+        // Endpoints, midpoint, both RGBA colors, then linear blending in RGB
+        // with both colors fixed rather than taken from the foreground or
+        // background
+        out.push_str(&format!(
+            "{start:.6} {:.6} {end:.6} {:.6} {:.6} {:.6} 1.000000 {:.6} {:.6} {:.6} 1.000000 0 0 0 0\n",
+            (start + end) / 2.0,
+            unit(left.r),
+            unit(left.g),
+            unit(left.b),
+            unit(right.r),
+            unit(right.g),
+            unit(right.b)
+        ));
+    }
+    out
+}
+
+// Build a Procreate swatches JSON array: one `[r, g, b, a, colorSpace]`
+// entry per color with channels in [0, 1]
+pub fn palette_to_procreate_json(palette: &[Color]) -> String {
+    let entries: Vec<String> = palette
+        .iter()
+        .map(|color| {
+            format!(
+                "  [{:.6}, {:.6}, {:.6}, 1.000000, \"srgb\"]",
+                f32::from(color.r) / 255.0,
+                f32::from(color.g) / 255.0,
+                f32::from(color.b) / 255.0
+            )
+        })
+        .collect();
+    if entries.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+#[allow(dead_code)]
+// Read the colors back out of a Procreate swatches JSON array. Alpha is
+// accepted but dropped since `Color` is opaque.
+pub fn parse_procreate_json(json: &str) -> Result<Vec<Color>, ColorParseError> {
+    let document = json::parse(json).map_err(ColorParseError::Malformed)?;
+    let entries = document.as_array().ok_or_else(|| {
+        ColorParseError::Malformed("expected a top-level array of swatches".to_string())
+    })?;
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let invalid = |reason: String| ColorParseError::InvalidEntry { index, reason };
+            let fields = entry
+                .as_array()
+                .ok_or_else(|| invalid("swatch must be an array".to_string()))?;
+            if fields.len() < 3 {
+                return Err(invalid(format!(
+                    "expected at least 3 components, got {}",
+                    fields.len()
+                )));
+            }
+            if let Some(space) = fields.get(4) {
+                match space.as_str() {
+                    Some(s) if s.eq_ignore_ascii_case("srgb") => {}
+                    Some(s) => return Err(invalid(format!("unsupported color space `{s}`"))),
+                    None => return Err(invalid("color space must be a string".to_string())),
+                }
+            }
+            let channel = |i: usize| -> Result<u8, ColorParseError> {
+                match fields[i].as_f64() {
+                    Some(v) if (0.0..=1.0).contains(&v) => Ok((v * 255.0).round() as u8),
+                    _ => Err(invalid(format!("component {i} must be a number in [0, 1]"))),
+                }
+            };
+            Ok(Color::from_rgb(channel(0)?, channel(1)?, channel(2)?))
+        })
+        .collect()
+}
+
+// Read a named palette from JSON. Accepts either an object mapping names
+// to hex codes, or an array whose items are hex strings or
+// `{"name": ..., "hex": ...}` objects.
+pub fn parse_palette_json(json: &str) -> Result<Vec<(String, Color)>, ColorParseError> {
+    let document = json::parse(json).map_err(ColorParseError::Malformed)?;
+    let hex_color = |index: usize, hex: &str| {
+        Color::from_hex(hex).map_err(|e| ColorParseError::InvalidEntry {
+            index,
+            reason: format!("`{hex}`: {e}"),
+        })
+    };
+
+    match &document {
+        Json::Object(members) => members
+            .iter()
+            .enumerate()
+            .map(|(index, (name, value))| {
+                let hex = value
+                    .as_str()
+                    .ok_or_else(|| ColorParseError::InvalidEntry {
+                        index,
+                        reason: format!("`{name}` must map to a hex string"),
+                    })?;
+                Ok((name.clone(), hex_color(index, hex)?))
+            })
+            .collect(),
+        Json::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| match item {
+                Json::String(hex) => Ok((hex.clone(), hex_color(index, hex)?)),
+                Json::Object(_) => {
+                    let field = |key: &str| {
+                        item.get(key).and_then(Json::as_str).ok_or_else(|| {
+                            ColorParseError::InvalidEntry {
+                                index,
+                                reason: format!("missing string field `{key}`"),
+                            }
+                        })
+                    };
+                    let hex = field("hex")?;
+                    let name = field("name").unwrap_or(hex);
+                    Ok((name.to_string(), hex_color(index, hex)?))
+                }
+                _ => Err(ColorParseError::InvalidEntry {
+                    index,
+                    reason: "expected a hex string or a {name, hex} object".to_string(),
+                }),
+            })
+            .collect(),
+        _ => Err(ColorParseError::Malformed(
+            "expected an object or an array of colors".to_string(),
+        )),
+    }
+}
+
+// Turn a free-form color name into a SCREAMING_SNAKE_CASE identifier
+fn screaming_snake_case(name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c.to_ascii_uppercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    let ident = words.join("_");
+    match ident.chars().next() {
+        None => "COLOR".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("COLOR_{ident}"),
+        Some(_) => ident,
+    }
+}
+
+// Lowercase Rust keywords, strict and reserved, up to the 2024 edition
+const RUST_KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// Turn a free-form name into a snake_case module name. Keywords become raw
+// identifiers, except `self`, `super` and `crate`, which cannot be raw and
+// get a trailing underscore instead.
+fn module_ident(name: &str) -> String {
+    let ident = screaming_snake_case(name).to_ascii_lowercase();
+    match ident.as_str() {
+        "self" | "super" | "crate" => format!("{ident}_"),
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{ident}"),
+        _ => ident,
+    }
+}
+
+// Emit a Rust module with one `pub const` per palette entry, plus an
+// optional `PALETTE` array. Names that collide after case conversion get a
+// `_2`, `_3`, ... suffix in palette order.
+pub fn palette_to_rust_module(
+    module: &str,
+    palette: &[(&str, Color)],
+    include_array: bool,
+) -> String {
+    let mut taken: Vec<String> = Vec::new();
+    if include_array {
+        taken.push("PALETTE".to_string());
+    }
+    let mut idents = Vec::with_capacity(palette.len());
+    for (name, _) in palette {
+        let base = screaming_snake_case(name);
+        let mut ident = base.clone();
+        let mut suffix = 2;
+        while taken.contains(&ident) {
+            ident = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        taken.push(ident.clone());
+        idents.push(ident);
+    }
+
+    let module = module_ident(module);
+    let mut out = format!("pub mod {module} {{\n    use super::Color;\n\n");
+    for (ident, (_, color)) in idents.iter().zip(palette) {
+        out.push_str(&format!(
+            "    pub const {ident}: Color = Color::new({}, {}, {});\n",
+            color.r, color.g, color.b
+        ));
+    }
+    if include_array {
+        out.push_str(&format!(
+            "\n    pub const PALETTE: [Color; {}] = [{}];\n",
+            idents.len(),
+            idents.join(", ")
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+// An ordered collection of colors
+#[derive(Debug, Clone, Default)]
+pub struct ColorPalette {
+    pub colors: Vec<Color>,
+}
+
+impl ColorPalette {
+    #[allow(dead_code)]
+    pub fn new(colors: Vec<Color>) -> Self {
+        Self { colors }
+    }
+
+    #[allow(dead_code)]
+    // Order colors along a Hilbert curve through the RGB cube, so similar
+    // colors end up next to each other
+    pub fn sort_hilbert(&mut self) {
+        self.colors.sort_by_key(Color::to_sort_key_hilbert);
+    }
+}
+
+impl Color {
+    // Position of the color on a 3D Hilbert curve through the RGB cube
+    // (8 bits per axis, so 24 significant bits). Uses Skilling's
+    // transpose formulation.
+    pub fn to_sort_key_hilbert(&self) -> u32 {
+        const BITS: u32 = 8;
+        let mut x = [u32::from(self.r), u32::from(self.g), u32::from(self.b)];
+
+        let mut q = 1 << (BITS - 1);
+        while q > 1 {
+            let p = q - 1;
+            for i in 0..3 {
+                if x[i] & q != 0 {
+                    x[0] ^= p;
+                } else {
+                    let t = (x[0] ^ x[i]) & p;
+                    x[0] ^= t;
+                    x[i] ^= t;
+                }
+            }
+            q >>= 1;
+        }
+
+        for i in 1..3 {
+            x[i] ^= x[i - 1];
+        }
+        let mut t = 0;
+        let mut q = 1 << (BITS - 1);
+        while q > 1 {
+            if x[2] & q != 0 {
+                t ^= q - 1;
+            }
+            q >>= 1;
+        }
+        for axis in &mut x {
+            *axis ^= t;
+        }
+
+        let mut key = 0;
+        for bit in (0..BITS).rev() {
+            for axis in &x {
+                key = (key << 1) | ((axis >> bit) & 1);
+            }
+        }
+        key
+    }
+
+    #[allow(dead_code)]
+    // `(h, s, l)` key for `sort_by`, ordering by hue around the color wheel
+    pub fn to_sort_key_hsl_cylinder(&self) -> (f32, f32, f32) {
+        self.to_hsl()
+    }
+}
+
+// Read a palette file: JSON (see `parse_palette_json`) or plain text with
+// one `hex` or `name: hex` entry per line. Blank lines and lines starting
+// with `#` followed by a space are skipped.
+pub fn parse_palette(text: &str) -> Result<Vec<(String, Color)>, ColorParseError> {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return parse_palette_json(text);
+    }
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("# "))
+        .enumerate()
+        .map(|(index, line)| {
+            let (name, hex) = match line.rsplit_once(':') {
+                Some((name, hex)) => (name.trim(), hex.trim()),
+                None => (line, line),
+            };
+            let color = Color::from_hex(hex).map_err(|e| ColorParseError::InvalidEntry {
+                index,
+                reason: format!("`{hex}`: {e}"),
+            })?;
+            Ok((name.to_string(), color))
+        })
+        .collect()
+}
+
+// The 216 web-safe colors, ordered by red, then green, then blue
+pub fn web_safe_palette() -> Vec<Color> {
+    let levels = [0, 51, 102, 153, 204, 255];
+    let mut colors = Vec::with_capacity(216);
+    for r in levels {
+        for g in levels {
+            for b in levels {
+                colors.push(Color::from_rgb(r, g, b));
+            }
+        }
+    }
+    colors
+}
+
+impl Color {
+    // Whether every channel is a multiple of 0x33
+    pub fn is_web_safe(&self) -> bool {
+        [self.r, self.g, self.b].iter().all(|c| c % 51 == 0)
+    }
+
+    // Perceptually nearest web-safe color (CIEDE2000). Ties go to the
+    // first candidate in `web_safe_palette` order.
+    pub fn to_web_safe(&self) -> Color {
+        if self.is_web_safe() {
+            return self.clone();
+        }
+        let mut best = Color::from_rgb(0, 0, 0);
+        let mut best_distance = f32::INFINITY;
+        for candidate in web_safe_palette() {
+            let distance = self.delta_e(&candidate);
+            if distance < best_distance {
+                best_distance = distance;
+                best = candidate;
+            }
+        }
+        best
+    }
+}
+
+#[allow(dead_code)]
+// Serialize a single-palette OpenType CPAL table (version 0). All
+// integers are big-endian and each color record is BGRA. The table counts
+// colors in 16 bits, so larger palettes are rejected.
+pub fn palette_to_cpal_bytes(palette: &[(Color, u8)]) -> Result<Vec<u8>, ColorParseError> {
+    let entries = u16::try_from(palette.len()).map_err(|_| {
+        ColorParseError::Unsupported(format!(
+            "CPAL holds at most 65535 colors, got {}",
+            palette.len()
+        ))
+    })?;
+    // version, numPaletteEntries, numPalettes, numColorRecords, then the
+    // 32-bit offset of the records, which follow the one palette index
+    let records_offset: u32 = 14;
+    let mut out = Vec::with_capacity(records_offset as usize + palette.len() * 4);
+    for field in [0, entries, 1, entries] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+    out.extend_from_slice(&records_offset.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    for (color, alpha) in palette {
+        out.extend_from_slice(&color.to_opentype_bgra(*alpha));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inkscape_palette_round_trips_names_and_values() {
+        let palette = [
+            ("Red", Color::from_rgb(255, 0, 0)),
+            ("Sea & Sky", Color::from_rgb(0, 128, 255)),
+            ("\"Quoted\" <gray>", Color::from_rgb(128, 128, 128)),
+            ("", Color::from_rgb(1, 2, 3)),
+            ("Amber 500", Color::from_rgb(255, 136, 0)),
+        ];
+        let xml = palette_to_inkscape_xml("Test palette", &palette);
+        let parsed = parse_inkscape_palette_xml(&xml).unwrap();
+
+        let expected: Vec<(String, Color)> = palette
+            .iter()
+            .map(|(name, color)| (name.to_string(), color.clone()))
+            .collect();
+        assert_eq!(parsed, expected);
+    }
+
+    fn brand_palette() -> [(&'static str, Color); 3] {
+        [
+            ("Amber 500", Color::from_rgb(255, 136, 0)),
+            ("amber-500", Color::from_rgb(255, 200, 0)),
+            ("primaryBlue", Color::from_rgb(0, 0, 255)),
+        ]
+    }
+
+    #[test]
+    fn rust_module_matches_golden_snapshot() {
+        let expected = "\
+pub mod brand_colors {
+    use super::Color;
+
+    pub const AMBER_500: Color = Color::new(255, 136, 0);
+    pub const AMBER_500_2: Color = Color::new(255, 200, 0);
+    pub const PRIMARY_BLUE: Color = Color::new(0, 0, 255);
+
+    pub const PALETTE: [Color; 3] = [AMBER_500, AMBER_500_2, PRIMARY_BLUE];
+}
+";
+        assert_eq!(
+            palette_to_rust_module("Brand Colors", &brand_palette(), true),
+            expected
+        );
+    }
+
+    #[test]
+    fn rust_module_suffixes_collisions_in_palette_order() {
+        let palette = [
+            ("amber-500", Color::from_rgb(1, 1, 1)),
+            ("Amber 500", Color::from_rgb(2, 2, 2)),
+            ("AMBER_500", Color::from_rgb(3, 3, 3)),
+            ("palette", Color::from_rgb(4, 4, 4)),
+        ];
+        let module = palette_to_rust_module("colors", &palette, true);
+        assert!(module.contains("pub const AMBER_500: Color = Color::new(1, 1, 1);"));
+        assert!(module.contains("pub const AMBER_500_2: Color = Color::new(2, 2, 2);"));
+        assert!(module.contains("pub const AMBER_500_3: Color = Color::new(3, 3, 3);"));
+        assert!(module.contains("pub const PALETTE_2: Color = Color::new(4, 4, 4);"));
+    }
+
+    #[test]
+    fn rust_module_escapes_keyword_module_names() {
+        let header = |name: &str| {
+            palette_to_rust_module(name, &[], false)
+                .lines()
+                .next()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(header("type"), "pub mod r#type {");
+        assert_eq!(header("Mod"), "pub mod r#mod {");
+        assert_eq!(header("self"), "pub mod self_ {");
+        assert_eq!(header("crate"), "pub mod crate_ {");
+        assert_eq!(header("types"), "pub mod types {");
+    }
+
+    #[test]
+    fn rust_module_compiles() {
+        let mut source = String::from(
+            "pub struct Color(u8, u8, u8);\n\
+             impl Color {\n    \
+                 pub const fn new(r: u8, g: u8, b: u8) -> Self {\n        \
+                     Self(r, g, b)\n    \
+                 }\n\
+             }\n",
+        );
+        for module in ["Brand Colors", "type", "self", "super", "500"] {
+            source.push_str(&palette_to_rust_module(module, &brand_palette(), true));
+        }
+        source.push_str(&palette_to_rust_module("empty", &[], true));
+
+        let dir = std::env::temp_dir().join(format!("chromatic-rust-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("palette.rs");
+        std::fs::write(&file, &source).unwrap();
+        let output = std::process::Command::new("rustc")
+            .args([
+                "--edition",
+                "2021",
+                "--crate-type",
+                "lib",
+                "--emit",
+                "metadata",
+            ])
+            .arg("--out-dir")
+            .arg(&dir)
+            .arg(&file)
+            .output()
+            .expect("rustc should be on PATH while running tests");
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(
+            output.status.success(),
+            "generated module does not compile:\n{}\n{source}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn cpal_bytes_follow_the_table_layout() {
+        let palette = [
+            (Color::from_rgb(0x11, 0x22, 0x33), 0xFF),
+            (Color::from_rgb(0xAA, 0xBB, 0xCC), 0x80),
+        ];
+        let bytes = palette_to_cpal_bytes(&palette).unwrap();
+        assert_eq!(
+            bytes,
+            [
+                0, 0, // version
+                0, 2, // numPaletteEntries
+                0, 1, // numPalettes
+                0, 2, // numColorRecords
+                0, 0, 0, 14, // colorRecordsArrayOffset
+                0, 0, // colorRecordIndices[0]
+                0x33, 0x22, 0x11, 0xFF, // first record, BGRA
+                0xCC, 0xBB, 0xAA, 0x80,
+            ]
+        );
+    }
+
+    #[test]
+    fn cpal_rejects_oversized_palettes() {
+        let palette = vec![(Color::from_rgb(0, 0, 0), 255); 65_536];
+        assert!(matches!(
+            palette_to_cpal_bytes(&palette),
+            Err(ColorParseError::Unsupported(_))
+        ));
+        assert_eq!(
+            palette_to_cpal_bytes(&palette[..65_535]).unwrap().len(),
+            14 + 65_535 * 4
+        );
+    }
+
+    #[test]
+    fn opentype_bgra_round_trips() {
+        let color = Color::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(color.to_opentype_bgra(0x78), [0x56, 0x34, 0x12, 0x78]);
+        assert_eq!(
+            Color::from_opentype_bgra(color.to_opentype_bgra(0x78)),
+            (color, 0x78)
+        );
+    }
+
+    #[test]
+    fn procreate_fixture_parses() {
+        let fixture = r#"[
+            [0.8509804, 0.2745098, 0.1372549, 1.0, "srgb"],
+            [0.0, 0.5, 1.0, 0.75, "sRGB"],
+            [1, 1, 1]
+        ]"#;
+        let colors = parse_procreate_json(fixture).unwrap();
+        assert_eq!(
+            colors,
+            [
+                Color::from_rgb(217, 70, 35),
+                Color::from_rgb(0, 128, 255),
+                Color::from_rgb(255, 255, 255),
+            ]
+        );
+    }
+
+    #[test]
+    fn procreate_rejects_bad_swatches() {
+        for (fixture, index) in [
+            (r#"[[0, 0, 0], [0, 0]]"#, 1),
+            (r#"[[0, 0, 1.5]]"#, 0),
+            (r#"[[0, 0, 0, 1, "p3"]]"#, 0),
+            (r##"["#FF0000"]"##, 0),
+        ] {
+            assert!(
+                matches!(
+                    parse_procreate_json(fixture),
+                    Err(ColorParseError::InvalidEntry { index: i, .. }) if i == index
+                ),
+                "{fixture}"
+            );
+        }
+        assert!(matches!(
+            parse_procreate_json("{}"),
+            Err(ColorParseError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn procreate_round_trip_preserves_every_entry() {
+        let palette = web_safe_palette();
+        let json = palette_to_procreate_json(&palette);
+        let parsed = parse_procreate_json(&json).unwrap();
+        assert_eq!(parsed.len(), palette.len());
+        for (before, after) in palette.iter().zip(&parsed) {
+            for (a, b) in [
+                (before.r, after.r),
+                (before.g, after.g),
+                (before.b, after.b),
+            ] {
+                assert!(a.abs_diff(b) <= 1, "{before:?} became {after:?}");
+            }
+        }
+        assert_eq!(palette_to_procreate_json(&[]), "[]\n");
+        assert_eq!(parse_procreate_json("[]\n").unwrap(), []);
+    }
+
+    #[test]
+    fn hsl_cylinder_key_sorts_a_rainbow_in_hue_order() {
+        let rainbow = [
+            Color::from_rgb(255, 0, 0),
+            Color::from_rgb(255, 128, 0),
+            Color::from_rgb(255, 255, 0),
+            Color::from_rgb(0, 255, 0),
+            Color::from_rgb(0, 255, 255),
+            Color::from_rgb(0, 0, 255),
+            Color::from_rgb(128, 0, 255),
+            Color::from_rgb(255, 0, 255),
+        ];
+        let mut shuffled = rainbow.to_vec();
+        shuffled.reverse();
+        shuffled.swap(1, 5);
+        shuffled.sort_by(|a, b| {
+            a.to_sort_key_hsl_cylinder()
+                .partial_cmp(&b.to_sort_key_hsl_cylinder())
+                .unwrap()
+        });
+        assert_eq!(shuffled, rainbow);
+    }
+
+    #[test]
+    fn hilbert_key_steps_between_neighbouring_colors() {
+        // The curve starts at black and fills the 16x16x16 corner cube
+        // first, so those keys are 0..4096 with every step moving one
+        // unit along one axis
+        let mut by_key = vec![None; 16 * 16 * 16];
+        for r in 0..16 {
+            for g in 0..16 {
+                for b in 0..16 {
+                    let key = Color::from_rgb(r, g, b).to_sort_key_hilbert() as usize;
+                    assert!(
+                        by_key[key].replace((r, g, b)).is_none(),
+                        "duplicate key {key}"
+                    );
+                }
+            }
+        }
+        let cells: Vec<(u8, u8, u8)> = by_key.into_iter().map(Option::unwrap).collect();
+        for pair in cells.windows(2) {
+            let ((r1, g1, b1), (r2, g2, b2)) = (pair[0], pair[1]);
+            let distance = r1.abs_diff(r2) + g1.abs_diff(g2) + b1.abs_diff(b2);
+            assert_eq!(distance, 1, "{:?} -> {:?}", pair[0], pair[1]);
+        }
+        assert_eq!(Color::from_rgb(0, 0, 0).to_sort_key_hilbert(), 0);
+    }
+
+    #[test]
+    fn sort_hilbert_orders_by_key() {
+        let mut palette = ColorPalette::new(web_safe_palette());
+        palette.sort_hilbert();
+        assert!(palette
+            .colors
+            .windows(2)
+            .all(|w| w[0].to_sort_key_hilbert() < w[1].to_sort_key_hilbert()));
+    }
+
+    #[test]
+    fn web_safe_colors_are_left_alone() {
+        for color in web_safe_palette() {
+            assert!(color.is_web_safe());
+            assert_eq!(color.to_web_safe(), color);
+        }
+        assert!(!Color::from_rgb(254, 0, 1).is_web_safe());
+    }
+
+    #[test]
+    fn near_web_safe_colors_snap_to_it() {
+        assert_eq!(
+            Color::from_rgb(254, 0, 1).to_web_safe(),
+            Color::from_rgb(255, 0, 0)
+        );
+        assert_eq!(
+            Color::from_rgb(50, 104, 152).to_web_safe(),
+            Color::from_rgb(51, 102, 153)
+        );
+    }
+
+    #[test]
+    fn web_safe_snapping_takes_the_first_of_equally_near_candidates() {
+        let candidates = web_safe_palette();
+        for r in (0..=255).step_by(17) {
+            for g in (0..=255).step_by(34) {
+                for b in (0..=255).step_by(51) {
+                    let color = Color::from_rgb(r, g, b);
+                    let nearest = candidates
+                        .iter()
+                        .map(|c| color.delta_e(c))
+                        .fold(f32::INFINITY, f32::min);
+                    let first = candidates
+                        .iter()
+                        .find(|c| color.delta_e(c) == nearest)
+                        .unwrap();
+                    let snapped = color.to_web_safe();
+                    assert_eq!(&snapped, first, "{color:?}");
+                    assert_eq!(color.to_web_safe(), snapped);
+                }
+            }
+        }
+    }
+
+    // Five entries of the Solarized Dark `.itermcolors` scheme, laid out the
+    // way iTerm2 writes it: Solarized's sRGB values as 32-bit floats. The
+    // cursor entry has no color space, like older exports.
+    const SOLARIZED_DARK: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Ansi 0 Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.25882354378700256</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.21176470816135406</real>
+		<key>Red Component</key>
+		<real>0.027450980618596077</real>
+	</dict>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.18431372940540314</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.19607843458652496</real>
+		<key>Red Component</key>
+		<real>0.8627451062202454</real>
+	</dict>
+	<key>Background Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.21176470816135406</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.16862745583057404</real>
+		<key>Red Component</key>
+		<real>0.0</real>
+	</dict>
+	<key>Cursor Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>0.6313725709915161</real>
+		<key>Green Component</key>
+		<real>0.6313725709915161</real>
+		<key>Red Component</key>
+		<real>0.5764706134796143</real>
+	</dict>
+	<key>Foreground Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.5882353186607361</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.5803921818733215</real>
+		<key>Red Component</key>
+		<real>0.5137255191802979</real>
+	</dict>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn iterm2_parses_solarized_dark() {
+        let colors = parse_iterm2_scheme(SOLARIZED_DARK).unwrap();
+        let names: Vec<&str> = colors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Ansi 0 Color",
+                "Ansi 1 Color",
+                "Background Color",
+                "Cursor Color",
+                "Foreground Color"
+            ]
+        );
+        let color = |name: &str| colors.iter().find(|(n, _)| n == name).unwrap().1.clone();
+        // Solarized base03, red, base0 and base1
+        assert_eq!(
+            color("Background Color"),
+            Color::from_hex("#002B36").unwrap()
+        );
+        assert_eq!(color("Ansi 1 Color"), Color::from_hex("#DC322F").unwrap());
+        assert_eq!(
+            color("Foreground Color"),
+            Color::from_hex("#839496").unwrap()
+        );
+        assert_eq!(color("Cursor Color"), Color::from_hex("#93A1A1").unwrap());
+    }
+
+    #[test]
+    fn iterm2_scheme_round_trips() {
+        let colors = parse_iterm2_scheme(SOLARIZED_DARK).unwrap();
+        let named: Vec<(&str, Color)> = colors
+            .iter()
+            .map(|(name, color)| (name.as_str(), color.clone()))
+            .collect();
+        let xml = generate_iterm2_scheme("Solarized -- Dark <copy>", &named);
+        assert_eq!(parse_iterm2_scheme(&xml).unwrap(), colors);
+        assert!(matches!(
+            parse_iterm2_scheme("<plist></plist>"),
+            Err(ColorParseError::Malformed(_))
+        ));
+    }
+
+    // Lines of a `.ggr` file that are segments: eleven decimals followed by
+    // the four integer blend and color types, the same check as the regex
+    // `^([0-9]+\.[0-9]{6} ){11}[0-9]+ [0-9]+ [0-9]+ [0-9]+$`
+    fn ggr_segments(ggr: &str) -> Vec<&str> {
+        ggr.lines()
+            .filter(|line| {
+                let fields: Vec<&str> = line.split(' ').collect();
+                fields.len() == 15
+                    && fields[..11].iter().all(|f| {
+                        f.split_once('.').is_some_and(|(int, frac)| {
+                            !int.is_empty()
+                                && int.bytes().all(|b| b.is_ascii_digit())
+                                && frac.len() == 6
+                                && frac.bytes().all(|b| b.is_ascii_digit())
+                        })
+                    })
+                    && fields[11..]
+                        .iter()
+                        .all(|f| !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gimp_gradient_has_one_segment_per_neighbor_pair() {
+        let colors = web_safe_palette();
+        for n in 2..=6 {
+            let ggr = palette_to_gimp_gradient("Test", &colors[..n]);
+            let mut lines = ggr.lines();
+            assert_eq!(lines.next(), Some("GIMP Gradient"));
+            assert_eq!(lines.next(), Some("Name: Test"));
+            assert_eq!(lines.next(), Some((n - 1).to_string().as_str()));
+            let segments = ggr_segments(&ggr);
+            assert_eq!(segments.len(), n - 1, "{ggr}");
+            assert_eq!(lines.count(), n - 1);
+            for segment in segments {
+                // Linear blending in RGB
+                assert!(segment.ends_with(" 0 0 0 0"), "{segment}");
+            }
+        }
+    }
+
+    #[test]
+    fn gimp_gradient_segments_are_equal_and_contiguous() {
+        let colors = [
+            Color::from_rgb(255, 0, 0),
+            Color::from_rgb(0, 255, 0),
+            Color::from_rgb(0, 0, 255),
+            Color::from_rgb(255, 255, 255),
+        ];
+        let ggr = palette_to_gimp_gradient("RGBW", &colors);
+        let bounds: Vec<(f64, f64)> = ggr_segments(&ggr)
+            .iter()
+            .map(|line| {
+                let fields: Vec<f64> = line
+                    .split(' ')
+                    .take(3)
+                    .map(|f| f.parse().unwrap())
+                    .collect();
+                (fields[0], fields[2])
+            })
+            .collect();
+        assert_eq!(bounds.first().map(|b| b.0), Some(0.0));
+        assert_eq!(bounds.last().map(|b| b.1), Some(1.0));
+        for (pair, (start, end)) in bounds.windows(2).zip(&bounds) {
+            assert_eq!(pair[0].1, pair[1].0);
+            assert!((end - start - 1.0 / 3.0).abs() < 1e-6);
+        }
+        assert!(ggr.contains(
+            "0.000000 0.166667 0.333333 1.000000 0.000000 0.000000 1.000000 \
+             0.000000 1.000000 0.000000 1.000000 0 0 0 0\n"
+        ));
+    }
+
+    #[test]
+    fn gimp_gradient_edge_cases() {
+        // A single color cannot make n - 1 = 0 segments GIMP will load, so it
+        // gives one flat segment instead
+        let ggr = palette_to_gimp_gradient("Flat", &[Color::from_rgb(255, 0, 0)]);
+        assert_eq!(ggr.lines().nth(2), Some("1"));
+        assert_eq!(
+            ggr_segments(&ggr),
+            [
+                "0.000000 0.500000 1.000000 1.000000 0.000000 0.000000 1.000000 \
+              1.000000 0.000000 0.000000 1.000000 0 0 0 0"
+            ]
+        );
+        let empty = palette_to_gimp_gradient("Empty", &[]);
+        assert_eq!(empty, "GIMP Gradient\nName: Empty\n0\n");
+        assert!(palette_to_gimp_gradient("Two\nLines", &[])
+            .starts_with("GIMP Gradient\nName: Two Lines\n"));
+    }
+}
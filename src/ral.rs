@@ -0,0 +1,293 @@
+use crate::Color;
+
+// RAL Classic codes with their names and commonly used sRGB approximations.
+// RAL publishes no official sRGB values, so these are only a guide: the
+// physical samples are the reference.
+const RAL_CLASSIC: [(u16, &str, [u8; 3]); 211] = [
+    (1000, "Green beige", [190, 189, 127]),
+    (1001, "Beige", [194, 176, 120]),
+    (1002, "Sand yellow", [198, 166, 100]),
+    (1003, "Signal yellow", [229, 190, 1]),
+    (1004, "Golden yellow", [205, 164, 52]),
+    (1005, "Honey yellow", [169, 131, 7]),
+    (1006, "Maize yellow", [228, 160, 16]),
+    (1007, "Daffodil yellow", [220, 157, 0]),
+    (1011, "Brown beige", [138, 102, 66]),
+    (1012, "Lemon yellow", [199, 180, 70]),
+    (1013, "Oyster white", [234, 230, 202]),
+    (1014, "Ivory", [225, 204, 79]),
+    (1015, "Light ivory", [230, 214, 144]),
+    (1016, "Sulfur yellow", [237, 255, 33]),
+    (1017, "Saffron yellow", [245, 208, 51]),
+    (1018, "Zinc yellow", [248, 243, 43]),
+    (1019, "Grey beige", [158, 151, 100]),
+    (1020, "Olive yellow", [153, 153, 80]),
+    (1021, "Rapeseed yellow", [243, 218, 11]),
+    (1023, "Traffic yellow", [250, 210, 1]),
+    (1024, "Ochre yellow", [174, 160, 75]),
+    (1026, "Luminous yellow", [255, 255, 0]),
+    (1027, "Curry", [157, 145, 1]),
+    (1028, "Melon yellow", [244, 169, 0]),
+    (1032, "Broom yellow", [214, 174, 1]),
+    (1033, "Dahlia yellow", [243, 165, 5]),
+    (1034, "Pastel yellow", [239, 169, 74]),
+    (1035, "Pearl beige", [106, 93, 77]),
+    (1036, "Pearl gold", [112, 83, 53]),
+    (1037, "Sun yellow", [243, 159, 24]),
+    (2000, "Yellow orange", [237, 118, 14]),
+    (2001, "Red orange", [201, 60, 32]),
+    (2002, "Vermilion", [203, 40, 33]),
+    (2003, "Pastel orange", [255, 117, 20]),
+    (2004, "Pure orange", [244, 70, 17]),
+    (2005, "Luminous orange", [255, 35, 1]),
+    (2007, "Luminous bright orange", [255, 164, 32]),
+    (2008, "Bright red orange", [247, 94, 37]),
+    (2009, "Traffic orange", [245, 64, 33]),
+    (2010, "Signal orange", [216, 75, 32]),
+    (2011, "Deep orange", [236, 124, 38]),
+    (2012, "Salmon orange", [229, 81, 55]),
+    (2013, "Pearl orange", [195, 88, 49]),
+    (3000, "Flame red", [175, 43, 30]),
+    (3001, "Signal red", [165, 32, 25]),
+    (3002, "Carmine red", [162, 35, 29]),
+    (3003, "Ruby red", [155, 17, 30]),
+    (3004, "Purple red", [117, 21, 30]),
+    (3005, "Wine red", [94, 33, 41]),
+    (3007, "Black red", [65, 34, 39]),
+    (3009, "Oxide red", [100, 36, 36]),
+    (3011, "Brown red", [120, 31, 25]),
+    (3012, "Beige red", [193, 135, 107]),
+    (3013, "Tomato red", [161, 35, 18]),
+    (3014, "Antique pink", [211, 110, 112]),
+    (3015, "Light pink", [234, 137, 154]),
+    (3016, "Coral red", [179, 40, 33]),
+    (3017, "Rose", [230, 50, 68]),
+    (3018, "Strawberry red", [213, 48, 50]),
+    (3020, "Traffic red", [204, 6, 5]),
+    (3022, "Salmon pink", [217, 80, 48]),
+    (3024, "Luminous red", [248, 0, 0]),
+    (3026, "Luminous bright red", [254, 0, 0]),
+    (3027, "Raspberry red", [197, 29, 52]),
+    (3028, "Pure red", [203, 50, 52]),
+    (3031, "Orient red", [179, 36, 40]),
+    (3032, "Pearl ruby red", [114, 20, 34]),
+    (3033, "Pearl pink", [180, 76, 67]),
+    (4001, "Red lilac", [109, 63, 91]),
+    (4002, "Red violet", [146, 43, 62]),
+    (4003, "Heather violet", [222, 76, 138]),
+    (4004, "Claret violet", [100, 28, 52]),
+    (4005, "Blue lilac", [108, 70, 117]),
+    (4006, "Traffic purple", [160, 52, 114]),
+    (4007, "Purple violet", [74, 25, 44]),
+    (4008, "Signal violet", [146, 78, 125]),
+    (4009, "Pastel violet", [161, 133, 148]),
+    (4010, "Telemagenta", [207, 52, 118]),
+    (4011, "Pearl violet", [134, 115, 161]),
+    (4012, "Pearl blackberry", [108, 104, 116]),
+    (5000, "Violet blue", [53, 77, 115]),
+    (5001, "Green blue", [31, 52, 56]),
+    (5002, "Ultramarine blue", [32, 33, 79]),
+    (5003, "Sapphire blue", [29, 30, 51]),
+    (5004, "Black blue", [24, 23, 28]),
+    (5005, "Signal blue", [30, 36, 96]),
+    (5007, "Brilliant blue", [62, 95, 138]),
+    (5008, "Grey blue", [38, 37, 45]),
+    (5009, "Azure blue", [2, 86, 105]),
+    (5010, "Gentian blue", [14, 41, 75]),
+    (5011, "Steel blue", [35, 26, 36]),
+    (5012, "Light blue", [59, 131, 189]),
+    (5013, "Cobalt blue", [30, 33, 61]),
+    (5014, "Pigeon blue", [96, 110, 140]),
+    (5015, "Sky blue", [34, 113, 179]),
+    (5017, "Traffic blue", [6, 57, 113]),
+    (5018, "Turquoise blue", [63, 136, 143]),
+    (5019, "Capri blue", [27, 85, 131]),
+    (5020, "Ocean blue", [29, 51, 74]),
+    (5021, "Water blue", [37, 109, 123]),
+    (5022, "Night blue", [37, 40, 80]),
+    (5023, "Distant blue", [73, 103, 141]),
+    (5024, "Pastel blue", [93, 155, 155]),
+    (5025, "Pearl gentian blue", [42, 100, 120]),
+    (5026, "Pearl night blue", [16, 44, 84]),
+    (6000, "Patina green", [49, 102, 80]),
+    (6001, "Emerald green", [40, 114, 51]),
+    (6002, "Leaf green", [45, 87, 44]),
+    (6003, "Olive green", [66, 70, 50]),
+    (6004, "Blue green", [31, 58, 61]),
+    (6005, "Moss green", [47, 69, 56]),
+    (6006, "Grey olive", [62, 59, 50]),
+    (6007, "Bottle green", [52, 59, 41]),
+    (6008, "Brown green", [57, 53, 42]),
+    (6009, "Fir green", [49, 55, 43]),
+    (6010, "Grass green", [53, 104, 45]),
+    (6011, "Reseda green", [88, 114, 70]),
+    (6012, "Black green", [52, 62, 64]),
+    (6013, "Reed green", [108, 113, 86]),
+    (6014, "Yellow olive", [71, 64, 46]),
+    (6015, "Black olive", [59, 60, 54]),
+    (6016, "Turquoise green", [30, 89, 69]),
+    (6017, "May green", [76, 145, 65]),
+    (6018, "Yellow green", [87, 166, 57]),
+    (6019, "Pastel green", [189, 236, 182]),
+    (6020, "Chrome green", [46, 58, 35]),
+    (6021, "Pale green", [137, 172, 118]),
+    (6022, "Olive drab", [37, 34, 27]),
+    (6024, "Traffic green", [48, 132, 70]),
+    (6025, "Fern green", [61, 100, 45]),
+    (6026, "Opal green", [1, 93, 82]),
+    (6027, "Light green", [132, 195, 190]),
+    (6028, "Pine green", [44, 85, 69]),
+    (6029, "Mint green", [32, 96, 61]),
+    (6032, "Signal green", [49, 127, 67]),
+    (6033, "Mint turquoise", [73, 126, 118]),
+    (6034, "Pastel turquoise", [127, 181, 181]),
+    (6035, "Pearl green", [28, 84, 45]),
+    (6036, "Pearl opal green", [25, 55, 55]),
+    (6037, "Pure green", [0, 143, 57]),
+    (6038, "Luminous green", [0, 187, 45]),
+    (7000, "Squirrel grey", [120, 133, 139]),
+    (7001, "Silver grey", [138, 149, 151]),
+    (7002, "Olive grey", [126, 123, 82]),
+    (7003, "Moss grey", [108, 112, 89]),
+    (7004, "Signal grey", [150, 153, 146]),
+    (7005, "Mouse grey", [100, 107, 99]),
+    (7006, "Beige grey", [109, 101, 82]),
+    (7008, "Khaki grey", [106, 95, 49]),
+    (7009, "Green grey", [77, 86, 69]),
+    (7010, "Tarpaulin grey", [76, 81, 74]),
+    (7011, "Iron grey", [67, 75, 77]),
+    (7012, "Basalt grey", [78, 87, 84]),
+    (7013, "Brown grey", [70, 69, 49]),
+    (7015, "Slate grey", [67, 71, 80]),
+    (7016, "Anthracite grey", [41, 49, 51]),
+    (7021, "Black grey", [35, 40, 43]),
+    (7022, "Umbra grey", [51, 47, 44]),
+    (7023, "Concrete grey", [104, 108, 94]),
+    (7024, "Graphite grey", [71, 74, 81]),
+    (7026, "Granite grey", [47, 53, 59]),
+    (7030, "Stone grey", [139, 140, 122]),
+    (7031, "Blue grey", [71, 75, 78]),
+    (7032, "Pebble grey", [184, 183, 153]),
+    (7033, "Cement grey", [125, 132, 113]),
+    (7034, "Yellow grey", [143, 139, 102]),
+    (7035, "Light grey", [215, 215, 215]),
+    (7036, "Platinum grey", [127, 118, 121]),
+    (7037, "Dusty grey", [125, 127, 125]),
+    (7038, "Agate grey", [181, 184, 177]),
+    (7039, "Quartz grey", [108, 105, 96]),
+    (7040, "Window grey", [157, 161, 170]),
+    (7042, "Traffic grey A", [141, 148, 141]),
+    (7043, "Traffic grey B", [78, 84, 82]),
+    (7044, "Silk grey", [202, 196, 176]),
+    (7045, "Telegrey 1", [144, 144, 144]),
+    (7046, "Telegrey 2", [130, 137, 143]),
+    (7047, "Telegrey 4", [208, 208, 208]),
+    (7048, "Pearl mouse grey", [137, 129, 118]),
+    (8000, "Green brown", [130, 108, 52]),
+    (8001, "Ochre brown", [149, 95, 32]),
+    (8002, "Signal brown", [108, 59, 42]),
+    (8003, "Clay brown", [115, 66, 34]),
+    (8004, "Copper brown", [142, 64, 42]),
+    (8007, "Fawn brown", [89, 53, 31]),
+    (8008, "Olive brown", [111, 79, 40]),
+    (8011, "Nut brown", [91, 58, 41]),
+    (8012, "Red brown", [89, 35, 33]),
+    (8014, "Sepia brown", [56, 44, 30]),
+    (8015, "Chestnut brown", [99, 58, 52]),
+    (8016, "Mahogany brown", [76, 47, 39]),
+    (8017, "Chocolate brown", [69, 50, 46]),
+    (8019, "Grey brown", [64, 58, 58]),
+    (8022, "Black brown", [33, 33, 33]),
+    (8023, "Orange brown", [166, 94, 46]),
+    (8024, "Beige brown", [121, 85, 61]),
+    (8025, "Pale brown", [117, 92, 72]),
+    (8028, "Terra brown", [78, 59, 49]),
+    (8029, "Pearl copper", [118, 60, 40]),
+    (9001, "Cream", [253, 244, 227]),
+    (9002, "Grey white", [231, 235, 218]),
+    (9003, "Signal white", [244, 244, 244]),
+    (9004, "Signal black", [40, 40, 40]),
+    (9005, "Jet black", [10, 10, 10]),
+    (9006, "White aluminium", [165, 165, 165]),
+    (9007, "Grey aluminium", [143, 143, 143]),
+    (9010, "Pure white", [255, 255, 255]),
+    (9011, "Graphite black", [28, 28, 28]),
+    (9016, "Traffic white", [246, 246, 246]),
+    (9017, "Traffic black", [30, 30, 30]),
+    (9018, "Papyrus white", [215, 215, 215]),
+];
+
+// Name of a RAL Classic color, e.g. "Traffic red" for 3020
+pub fn ral_classic_name(code: u16) -> Option<&'static str> {
+    RAL_CLASSIC
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, name, _)| *name)
+}
+
+impl Color {
+    // Approximate color of a RAL Classic code, or `None` for unknown codes
+    pub fn from_ral_classic(code: u16) -> Option<Color> {
+        RAL_CLASSIC
+            .iter()
+            .find(|(c, _, _)| *c == code)
+            .map(|(_, _, [r, g, b])| Color::from_rgb(*r, *g, *b))
+    }
+
+    #[allow(dead_code)]
+    // The RAL Classic color closest to this one by CIEDE2000, with ties
+    // going to the lower code
+    pub fn to_nearest_ral_classic(&self) -> (u16, Color) {
+        RAL_CLASSIC
+            .iter()
+            .map(|(code, _, [r, g, b])| (*code, Color::from_rgb(*r, *g, *b)))
+            .min_by(|(_, a), (_, b)| self.delta_e(a).total_cmp(&self.delta_e(b)))
+            .expect("the RAL table is not empty")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traffic_red_round_trips() {
+        let red = Color::from_ral_classic(3020).unwrap();
+        let expected = Color::from_rgb(204, 6, 5);
+        assert!(red.delta_e(&expected) < 1.0, "{red:?}");
+        assert_eq!(expected.to_nearest_ral_classic().0, 3020);
+        assert_eq!(ral_classic_name(3020), Some("Traffic red"));
+    }
+
+    #[test]
+    fn nearest_ties_go_to_the_lower_code() {
+        // Light grey and Papyrus white share an sRGB approximation
+        let shared = Color::from_rgb(215, 215, 215);
+        assert_eq!(Color::from_ral_classic(7035), Some(shared.clone()));
+        assert_eq!(Color::from_ral_classic(9018), Some(shared.clone()));
+        assert_eq!(shared.to_nearest_ral_classic(), (7035, shared.clone()));
+        assert_eq!(
+            Color::from_rgb(214, 214, 214).to_nearest_ral_classic(),
+            (7035, shared)
+        );
+    }
+
+    #[test]
+    fn every_entry_is_its_own_nearest_match_or_an_exact_duplicate() {
+        for (code, _, [r, g, b]) in RAL_CLASSIC {
+            let color = Color::from_rgb(r, g, b);
+            let (nearest, nearest_color) = color.to_nearest_ral_classic();
+            assert!(
+                nearest == code || (nearest < code && nearest_color == color),
+                "{code} matched {nearest}"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_codes_are_none() {
+        assert_eq!(Color::from_ral_classic(0), None);
+        assert_eq!(Color::from_ral_classic(3999), None);
+        assert_eq!(ral_classic_name(1), None);
+    }
+}
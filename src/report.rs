@@ -0,0 +1,278 @@
+use crate::a11y::{Deficiency, AA_LARGE, AA_NORMAL};
+use crate::Color;
+
+// A pair that is easy to tell apart normally counts as a CVD conflict when
+// the simulated colors fall below this CIEDE2000 distance
+const DISTINCT_DELTA_E: f32 = 10.0;
+const CONFUSABLE_DELTA_E: f32 = 5.0;
+
+// Which sections to include in the report
+#[derive(Debug, Clone, Copy)]
+pub struct ReportOptions {
+    pub contrast: bool,
+    pub cvd: bool,
+}
+
+struct CvdConflict<'a> {
+    deficiency: Deficiency,
+    a: &'a str,
+    b: &'a str,
+    normal: f32,
+    simulated: f32,
+}
+
+fn hex_digits(color: &Color) -> String {
+    color.to_hex().trim_start_matches('#').to_string()
+}
+
+// Names end up inside table cells, so pipes must be escaped
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn contrast_mark(ratio: f64) -> &'static str {
+    if ratio >= AA_NORMAL {
+        "✅"
+    } else if ratio >= AA_LARGE {
+        "🔶"
+    } else {
+        "❌"
+    }
+}
+
+// Unordered pairs of palette indices, in palette order
+fn pairs(len: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..len).flat_map(move |i| (i + 1..len).map(move |j| (i, j)))
+}
+
+fn cvd_conflicts(palette: &[(String, Color)]) -> Vec<CvdConflict<'_>> {
+    let mut conflicts = Vec::new();
+    for deficiency in Deficiency::ALL {
+        let simulated: Vec<Color> = palette
+            .iter()
+            .map(|(_, c)| c.simulate_cvd(deficiency))
+            .collect();
+        for (i, j) in pairs(palette.len()) {
+            let normal = palette[i].1.delta_e(&palette[j].1);
+            let seen = simulated[i].delta_e(&simulated[j]);
+            if normal >= DISTINCT_DELTA_E && seen < CONFUSABLE_DELTA_E {
+                conflicts.push(CvdConflict {
+                    deficiency,
+                    a: &palette[i].0,
+                    b: &palette[j].0,
+                    normal,
+                    simulated: seen,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+// Render a Markdown accessibility report for a named palette. The output
+// only depends on the palette and options, so it can be snapshot tested.
+pub fn markdown_report(palette: &[(String, Color)], options: ReportOptions) -> String {
+    let mut out = String::from("# Accessibility report\n\n");
+
+    out.push_str("- [Palette](#palette)\n");
+    if options.contrast {
+        out.push_str("- [Contrast](#contrast)\n");
+    }
+    if options.cvd {
+        out.push_str("- [Color vision deficiency](#color-vision-deficiency)\n");
+    }
+    out.push_str("- [Summary](#summary)\n\n");
+
+    out.push_str("## Palette\n\n| Name | Hex | Swatch |\n| --- | --- | --- |\n");
+    for (name, color) in palette {
+        let hex = hex_digits(color);
+        out.push_str(&format!(
+            "| {} | `#{hex}` | ![#{hex}](https://img.shields.io/badge/%20-%20-{hex}?style=flat-square) |\n",
+            cell(name)
+        ));
+    }
+    out.push('\n');
+
+    let mut failures = Vec::new();
+    if options.contrast {
+        out.push_str("## Contrast\n\n");
+        out.push_str(
+            "WCAG 2 contrast ratio for each pair: ✅ passes AA (≥ 4.5), \
+             🔶 large text only (≥ 3), ❌ fails.\n\n",
+        );
+        out.push('|');
+        for (name, _) in palette {
+            out.push_str(&format!(" | {}", cell(name)));
+        }
+        out.push_str(" |\n| ---");
+        out.push_str(&" | ---".repeat(palette.len()));
+        out.push_str(" |\n");
+        for (row_name, row) in palette {
+            out.push_str(&format!("| **{}**", cell(row_name)));
+            for (_, column) in palette {
+                if std::ptr::eq(row, column) {
+                    out.push_str(" | —");
+                } else {
+                    let ratio = row.contrast_ratio(column);
+                    out.push_str(&format!(" | {ratio:.2} {}", contrast_mark(ratio)));
+                }
+            }
+            out.push_str(" |\n");
+        }
+        out.push('\n');
+
+        for (i, j) in pairs(palette.len()) {
+            let ratio = palette[i].1.contrast_ratio(&palette[j].1);
+            if ratio < AA_NORMAL {
+                failures.push((&palette[i].0, &palette[j].0, ratio));
+            }
+        }
+    }
+
+    let conflicts = if options.cvd {
+        cvd_conflicts(palette)
+    } else {
+        Vec::new()
+    };
+    if options.cvd {
+        out.push_str("## Color vision deficiency\n\n");
+        if conflicts.is_empty() {
+            out.push_str("No pairs become hard to distinguish under simulated protanopia, deuteranopia or tritanopia.\n\n");
+        } else {
+            out.push_str(&format!(
+                "Pairs that are distinct in normal vision (ΔE00 ≥ {DISTINCT_DELTA_E}) \
+                 but nearly identical when simulated (ΔE00 < {CONFUSABLE_DELTA_E}).\n\n"
+            ));
+            out.push_str("| Deficiency | Colors | ΔE00 normal | ΔE00 simulated |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+            for conflict in &conflicts {
+                out.push_str(&format!(
+                    "| {} | {} / {} | {:.1} | {:.1} |\n",
+                    conflict.deficiency.name(),
+                    cell(conflict.a),
+                    cell(conflict.b),
+                    conflict.normal,
+                    conflict.simulated
+                ));
+            }
+            out.push('\n');
+        }
+    }
+
+    out.push_str("## Summary\n\n");
+    let pair_count = pairs(palette.len()).count();
+    if options.contrast {
+        out.push_str(&format!(
+            "- {} of {pair_count} color pairs fail WCAG AA for normal text.\n",
+            failures.len()
+        ));
+    }
+    if options.cvd {
+        out.push_str(&format!(
+            "- {} color vision deficiency conflicts.\n",
+            conflicts.len()
+        ));
+    }
+    if !options.contrast && !options.cvd {
+        out.push_str("- No checks were requested.\n");
+    }
+    if !failures.is_empty() {
+        out.push_str("\n### Failing contrast pairs\n\n");
+        for (a, b, ratio) in &failures {
+            out.push_str(&format!("- {} on {}: {ratio:.2}:1\n", cell(a), cell(b)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Vec<(String, Color)> {
+        vec![
+            ("Blue".to_string(), Color::from_rgb(0, 0, 255)),
+            ("Violet".to_string(), Color::from_rgb(153, 0, 255)),
+            ("Paper | White".to_string(), Color::from_rgb(255, 255, 255)),
+        ]
+    }
+
+    #[test]
+    fn full_report_matches_snapshot() {
+        let expected = "\
+# Accessibility report
+
+- [Palette](#palette)
+- [Contrast](#contrast)
+- [Color vision deficiency](#color-vision-deficiency)
+- [Summary](#summary)
+
+## Palette
+
+| Name | Hex | Swatch |
+| --- | --- | --- |
+| Blue | `#0000FF` | ![#0000FF](https://img.shields.io/badge/%20-%20-0000FF?style=flat-square) |
+| Violet | `#9900FF` | ![#9900FF](https://img.shields.io/badge/%20-%20-9900FF?style=flat-square) |
+| Paper \\| White | `#FFFFFF` | ![#FFFFFF](https://img.shields.io/badge/%20-%20-FFFFFF?style=flat-square) |
+
+## Contrast
+
+WCAG 2 contrast ratio for each pair: ✅ passes AA (≥ 4.5), 🔶 large text only (≥ 3), ❌ fails.
+
+| | Blue | Violet | Paper \\| White |
+| --- | --- | --- | --- |
+| **Blue** | — | 1.55 ❌ | 8.59 ✅ |
+| **Violet** | 1.55 ❌ | — | 5.53 ✅ |
+| **Paper \\| White** | 8.59 ✅ | 5.53 ✅ | — |
+
+## Color vision deficiency
+
+Pairs that are distinct in normal vision (ΔE00 ≥ 10) but nearly identical when simulated (ΔE00 < 5).
+
+| Deficiency | Colors | ΔE00 normal | ΔE00 simulated |
+| --- | --- | --- | --- |
+| protanopia | Blue / Violet | 13.0 | 4.5 |
+
+## Summary
+
+- 1 of 3 color pairs fail WCAG AA for normal text.
+- 1 color vision deficiency conflicts.
+
+### Failing contrast pairs
+
+- Blue on Violet: 1.55:1
+";
+        let options = ReportOptions {
+            contrast: true,
+            cvd: true,
+        };
+        assert_eq!(markdown_report(&palette(), options), expected);
+    }
+
+    #[test]
+    fn sections_are_omitted_when_not_requested() {
+        let expected = "\
+# Accessibility report
+
+- [Palette](#palette)
+- [Summary](#summary)
+
+## Palette
+
+| Name | Hex | Swatch |
+| --- | --- | --- |
+| Blue | `#0000FF` | ![#0000FF](https://img.shields.io/badge/%20-%20-0000FF?style=flat-square) |
+| Violet | `#9900FF` | ![#9900FF](https://img.shields.io/badge/%20-%20-9900FF?style=flat-square) |
+| Paper \\| White | `#FFFFFF` | ![#FFFFFF](https://img.shields.io/badge/%20-%20-FFFFFF?style=flat-square) |
+
+## Summary
+
+- No checks were requested.
+";
+        let options = ReportOptions {
+            contrast: false,
+            cvd: false,
+        };
+        assert_eq!(markdown_report(&palette(), options), expected);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::a11y::AA_NORMAL;
+use crate::Color;
+use std::str::FromStr;
+
+// Hue offset of the neighbor in a compound scheme unless one is given
+pub const DEFAULT_COMPOUND_ANGLE: f32 = 30.0;
+
+// Color schemes that can be requested on the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    // Double-complementary: base, a neighbor hue and both complements
+    Compound(f32),
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    // Accepts `compound` or `compound:ANGLE`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "compound" => {
+                let angle = match arg {
+                    Some(arg) => arg
+                        .trim()
+                        .trim_end_matches("deg")
+                        .parse()
+                        .map_err(|_| format!("invalid compound angle `{arg}`"))?,
+                    None => DEFAULT_COMPOUND_ANGLE,
+                };
+                Ok(Scheme::Compound(angle))
+            }
+            _ => Err(format!(
+                "unknown scheme `{name}` (expected compound[:ANGLE])"
+            )),
+        }
+    }
+}
+
+impl Color {
+    // Same saturation and value, hue moved by `offset` degrees
+    fn with_hue_offset(&self, offset: f32) -> Color {
+        let (h, s, v) = self.to_hsv();
+        Color::from_hsv((h + offset).rem_euclid(360.0), s, v)
+    }
+
+    // Double-complementary scheme: the neighbor at `angle` degrees, the
+    // complement of the base, and the complement of the neighbor. With an
+    // angle of 0 the neighbor is the base itself and this degrades to the
+    // complementary pair.
+    pub fn compound(&self, angle: f32) -> [Color; 3] {
+        [
+            self.with_hue_offset(angle),
+            self.with_hue_offset(180.0),
+            self.with_hue_offset(angle + 180.0),
+        ]
+    }
+
+    // Triadic scheme: the hues 120° and 240° round from the base
+    pub fn triadic(&self) -> [Color; 2] {
+        [self.with_hue_offset(120.0), self.with_hue_offset(240.0)]
+    }
+
+    // Tetradic (square) scheme: the hues 90°, 180° and 270° round from the
+    // base
+    pub fn tetradic(&self) -> [Color; 3] {
+        [
+            self.with_hue_offset(90.0),
+            self.with_hue_offset(180.0),
+            self.with_hue_offset(270.0),
+        ]
+    }
+
+    // Split-complementary pair: the two hues 150° either side of the base
+    pub fn split_complementary(&self) -> [Color; 2] {
+        [self.with_hue_offset(150.0), self.with_hue_offset(210.0)]
+    }
+}
+
+// An album-art style palette built around one dominant color
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotifyPalette {
+    pub background: Color,
+    pub text: Color,
+    pub accent1: Color,
+    pub accent2: Color,
+}
+
+// A dark background in the dominant hue, near-white text that passes WCAG
+// AA on it, the dominant color at full saturation, and its first
+// split-complementary hue as a second accent
+pub fn generate_spotify_style_palette(dominant: &Color) -> SpotifyPalette {
+    let (h, s, l) = dominant.to_hsl();
+    let background = Color::from_hsl(h, s.min(0.6), (l * 0.35).min(0.2));
+    let text = [0.95, 0.98, 1.0]
+        .into_iter()
+        .map(|lightness| Color::from_hsl(h, 0.1, lightness))
+        .find(|text| text.contrast_ratio(&background) >= AA_NORMAL)
+        .unwrap_or_else(|| Color::from_rgb(255, 255, 255));
+    let [accent2, _] = dominant.split_complementary();
+    SpotifyPalette {
+        background,
+        text,
+        accent1: Color::from_hsl(h, 1.0, l),
+        accent2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_hue(color: &Color, expected: f32) {
+        let (h, _, _) = color.to_hsv();
+        // Hues only survive 8-bit channels to within about a degree
+        let diff = (h - expected).rem_euclid(360.0);
+        assert!(diff.min(360.0 - diff) < 1.0, "hue {h}, expected {expected}");
+    }
+
+    #[test]
+    fn compound_pins_the_four_hues_for_a_10_degree_base() {
+        let base = Color::from_hsv(10.0, 1.0, 1.0);
+        assert_hue(&base, 10.0);
+        let [neighbor, complement, neighbor_complement] = base.compound(DEFAULT_COMPOUND_ANGLE);
+        assert_hue(&neighbor, 40.0);
+        assert_hue(&complement, 190.0);
+        assert_hue(&neighbor_complement, 220.0);
+        for color in [&neighbor, &complement, &neighbor_complement] {
+            let (_, s, v) = color.to_hsv();
+            assert_eq!((s, v), (1.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn compound_wraps_hues_and_degrades_at_zero() {
+        let base = Color::from_hsv(350.0, 0.5, 0.8);
+        let [neighbor, complement, neighbor_complement] = base.compound(30.0);
+        assert_hue(&neighbor, 20.0);
+        assert_hue(&complement, 170.0);
+        assert_hue(&neighbor_complement, 200.0);
+
+        let [neighbor, complement, neighbor_complement] = base.compound(0.0);
+        assert_eq!(neighbor, base);
+        assert_eq!(neighbor_complement, complement);
+    }
+
+    #[test]
+    fn scheme_parses_an_optional_angle() {
+        assert_eq!(
+            "compound".parse(),
+            Ok(Scheme::Compound(DEFAULT_COMPOUND_ANGLE))
+        );
+        assert_eq!("Compound:45deg".parse(), Ok(Scheme::Compound(45.0)));
+        assert!("compound:wide".parse::<Scheme>().is_err());
+        assert!("triadic".parse::<Scheme>().is_err());
+    }
+
+    #[test]
+    fn spotify_text_passes_aa_on_the_background() {
+        for dominant in crate::palette::web_safe_palette() {
+            let palette = generate_spotify_style_palette(&dominant);
+            let ratio = palette.text.contrast_ratio(&palette.background);
+            assert!(ratio >= AA_NORMAL, "{dominant:?}: {ratio}");
+            assert!(
+                palette.background.relative_luminance() <= dominant.relative_luminance(),
+                "{dominant:?}"
+            );
+            assert_eq!(palette.accent2, dominant.split_complementary()[0]);
+        }
+    }
+
+    #[test]
+    fn spotify_accent_is_the_dominant_hue_at_full_saturation() {
+        let dominant = Color::from_rgb(0x33, 0x66, 0x99);
+        let palette = generate_spotify_style_palette(&dominant);
+        let (h, _, l) = dominant.to_hsl();
+        let (accent_h, accent_s, accent_l) = palette.accent1.to_hsl();
+        assert!((accent_h - h).abs() < 1.0);
+        assert!(accent_s > 0.99);
+        assert!((accent_l - l).abs() < 0.01);
+    }
+}
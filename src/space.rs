@@ -0,0 +1,680 @@
+use crate::Color;
+use std::str::FromStr;
+
+type Matrix = [[f32; 3]; 3];
+
+// Linear sRGB to CIE XYZ, D65 reference white
+const SRGB_TO_XYZ: Matrix = [
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175_0],
+    [0.019_333_9, 0.119_192, 0.950_304_1],
+];
+
+const XYZ_TO_SRGB: Matrix = [
+    [3.240_454_2, -1.537_138_5, -0.498_531_4],
+    [-0.969_266, 1.876_010_8, 0.041_556_0],
+    [0.055_643_4, -0.204_025_9, 1.057_225_2],
+];
+
+// Linear ProPhoto (ROMM) RGB to CIE XYZ, D50 reference white
+const PROPHOTO_TO_XYZ: Matrix = [
+    [0.797_674_9, 0.135_191_7, 0.031_353_4],
+    [0.288_040_2, 0.711_874_1, 0.000_085_7],
+    [0.0, 0.0, 0.825_21],
+];
+
+const XYZ_TO_PROPHOTO: Matrix = [
+    [1.345_943_3, -0.255_607_5, -0.051_111_8],
+    [-0.544_598_9, 1.508_167_3, 0.020_535_1],
+    [0.0, 0.0, 1.211_812_8],
+];
+
+// Bradford cone response and its inverse
+const BRADFORD: Matrix = [
+    [0.895_1, 0.266_4, -0.161_4],
+    [-0.750_2, 1.713_5, 0.036_7],
+    [0.038_9, -0.068_5, 1.029_6],
+];
+
+const BRADFORD_INV: Matrix = [
+    [0.986_992_9, -0.147_054_3, 0.159_962_7],
+    [0.432_305_3, 0.518_360_3, 0.049_291_2],
+    [-0.008_528_7, 0.040_042_8, 0.968_486_7],
+];
+
+fn mul(m: &Matrix, v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+// Reference white of a color space or viewing condition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhitePoint {
+    D50,
+    D65,
+    A,
+    E,
+    // Any other white given by its xy chromaticity
+    Custom { x: f32, y: f32 },
+}
+
+impl WhitePoint {
+    // XYZ of the white, normalised to Y = 1
+    pub fn xyz(&self) -> [f32; 3] {
+        match *self {
+            WhitePoint::D50 => [0.964_22, 1.0, 0.825_21],
+            WhitePoint::D65 => [0.950_47, 1.0, 1.088_83],
+            WhitePoint::A => [1.098_50, 1.0, 0.355_85],
+            WhitePoint::E => [1.0, 1.0, 1.0],
+            WhitePoint::Custom { x, y } => [x / y, 1.0, (1.0 - x - y) / y],
+        }
+    }
+
+    #[allow(dead_code)]
+    // xy chromaticity of the white
+    pub fn xy(&self) -> (f32, f32) {
+        match *self {
+            WhitePoint::Custom { x, y } => (x, y),
+            _ => {
+                let [x, y, z] = self.xyz();
+                let sum = x + y + z;
+                (x / sum, y / sum)
+            }
+        }
+    }
+}
+
+impl FromStr for WhitePoint {
+    type Err = String;
+
+    // Accepts d50, d65, a, e or a custom chromaticity written as "x,y"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "d50" => Ok(WhitePoint::D50),
+            "d65" => Ok(WhitePoint::D65),
+            "a" => Ok(WhitePoint::A),
+            "e" => Ok(WhitePoint::E),
+            other => {
+                let invalid =
+                    || format!("unknown white point `{s}` (expected d50, d65, a, e or x,y)");
+                let (x, y) = other.split_once(',').ok_or_else(invalid)?;
+                let x: f32 = x.trim().parse().map_err(|_| invalid())?;
+                let y: f32 = y.trim().parse().map_err(|_| invalid())?;
+                if !(x > 0.0 && y > 0.0 && x + y < 1.0) {
+                    return Err(format!("`{s}` is not a valid xy chromaticity"));
+                }
+                Ok(WhitePoint::Custom { x, y })
+            }
+        }
+    }
+}
+
+const DISPLAY_P3_TO_XYZ: Matrix = [
+    [0.486_570_94, 0.265_667_7, 0.198_217_29],
+    [0.228_974_56, 0.691_738_5, 0.079_286_91],
+    [0.0, 0.045_113_38, 1.043_944_4],
+];
+
+const XYZ_TO_DISPLAY_P3: Matrix = [
+    [2.493_497, -0.931_383_6, -0.402_710_8],
+    [-0.829_489, 1.762_664_1, 0.023_624_687],
+    [0.035_845_83, -0.076_172_39, 0.956_884_5],
+];
+
+// Linear sRGB for display-p3 encoded components, without clipping
+pub fn display_p3_to_linear_srgb(rgb: [f32; 3]) -> [f32; 3] {
+    let linear = rgb.map(srgb_to_linear);
+    xyz_to_linear_srgb(mul(&DISPLAY_P3_TO_XYZ, linear))
+}
+
+// Bradford chromatic adaptation of an XYZ triple from one white to another.
+// Adapting to the same white returns the input untouched.
+pub fn bradford_adapt(xyz: [f32; 3], from: WhitePoint, to: WhitePoint) -> [f32; 3] {
+    if from == to {
+        return xyz;
+    }
+    let src = mul(&BRADFORD, from.xyz());
+    let dst = mul(&BRADFORD, to.xyz());
+    let cone = mul(&BRADFORD, xyz);
+    let scaled = [
+        cone[0] * dst[0] / src[0],
+        cone[1] * dst[1] / src[1],
+        cone[2] * dst[2] / src[2],
+    ];
+    mul(&BRADFORD_INV, scaled)
+}
+
+// Linear sRGB for a D65 XYZ triple, without clipping to the gamut
+pub fn xyz_to_linear_srgb(xyz: [f32; 3]) -> [f32; 3] {
+    mul(&XYZ_TO_SRGB, xyz)
+}
+
+// Decode one sRGB-encoded channel. Values outside [0, 1] are extended
+// symmetrically around zero, as CSS does.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    let magnitude = c.abs();
+    let linear = if magnitude <= 0.040_45 {
+        magnitude / 12.92
+    } else {
+        ((magnitude + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(c)
+}
+
+// Encode one linear channel as sRGB, extended symmetrically like
+// `srgb_to_linear`
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let magnitude = c.abs();
+    let encoded = if magnitude <= 0.003_130_8 {
+        magnitude * 12.92
+    } else {
+        1.055 * magnitude.powf(1.0 / 2.4) - 0.055
+    };
+    encoded.copysign(c)
+}
+
+fn to_byte(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+fn lab_f(t: f32) -> f32 {
+    if t > LAB_EPSILON {
+        t.cbrt()
+    } else {
+        (LAB_KAPPA * t + 16.0) / 116.0
+    }
+}
+
+fn lab_f_inv(t: f32) -> f32 {
+    let cubed = t * t * t;
+    if cubed > LAB_EPSILON {
+        cubed
+    } else {
+        (116.0 * t - 16.0) / LAB_KAPPA
+    }
+}
+
+// D65 XYZ for CIELAB relative to the given reference white
+pub fn lab_to_xyz(l: f32, a: f32, b: f32, white: WhitePoint) -> [f32; 3] {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let [xn, yn, zn] = white.xyz();
+    let xyz = [xn * lab_f_inv(fx), yn * lab_f_inv(fy), zn * lab_f_inv(fz)];
+    bradford_adapt(xyz, white, WhitePoint::D65)
+}
+
+// CIELAB for an XYZ triple already relative to the given reference white
+pub fn xyz_to_lab(xyz: [f32; 3], white: WhitePoint) -> (f32, f32, f32) {
+    let [x, y, z] = xyz;
+    let [xn, yn, zn] = white.xyz();
+    let fx = lab_f(x / xn);
+    let fy = lab_f(y / yn);
+    let fz = lab_f(z / zn);
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+impl Color {
+    // Convert to linear-light sRGB components in [0, 1]
+    pub fn to_linear_rgb(&self) -> [f32; 3] {
+        [
+            srgb_to_linear(f32::from(self.r) / 255.0),
+            srgb_to_linear(f32::from(self.g) / 255.0),
+            srgb_to_linear(f32::from(self.b) / 255.0),
+        ]
+    }
+
+    // Constructor from linear-light sRGB components, clipped to the gamut
+    pub fn from_linear_rgb(rgb: [f32; 3]) -> Self {
+        Self {
+            r: to_byte(linear_to_srgb(rgb[0])),
+            g: to_byte(linear_to_srgb(rgb[1])),
+            b: to_byte(linear_to_srgb(rgb[2])),
+        }
+    }
+
+    // Convert to CIE XYZ relative to D65 (Y of white = 1)
+    pub fn to_xyz(&self) -> [f32; 3] {
+        mul(&SRGB_TO_XYZ, self.to_linear_rgb())
+    }
+
+    // Constructor from CIE XYZ relative to D65
+    pub fn from_xyz(xyz: [f32; 3]) -> Self {
+        Self::from_linear_rgb(xyz_to_linear_srgb(xyz))
+    }
+
+    // Convert to CIE XYZ adapted to the given reference white
+    pub fn to_xyz_with_white(&self, white: WhitePoint) -> [f32; 3] {
+        bradford_adapt(self.to_xyz(), WhitePoint::D65, white)
+    }
+
+    // Convert to CIELAB relative to D65
+    pub fn to_lab(&self) -> (f32, f32, f32) {
+        self.to_lab_with_white(WhitePoint::D65)
+    }
+
+    // Convert to CIELAB relative to the given reference white
+    pub fn to_lab_with_white(&self, white: WhitePoint) -> (f32, f32, f32) {
+        xyz_to_lab(self.to_xyz_with_white(white), white)
+    }
+
+    // Constructor from CIELAB relative to D65
+    pub fn from_lab(l: f32, a: f32, b: f32) -> Self {
+        Self::from_lab_with_white(l, a, b, WhitePoint::D65)
+    }
+
+    // Convert to CIE LCh(ab) relative to D65, with the hue in degrees
+    pub fn to_lch(&self) -> (f32, f32, f32) {
+        let (l, a, b) = self.to_lab();
+        (l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+    }
+
+    #[allow(dead_code)]
+    // Constructor from CIE LCh(ab) relative to D65, with the hue in degrees
+    pub fn from_lch(l: f32, c: f32, h: f32) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+        Self::from_lab(l, c * cos, c * sin)
+    }
+
+    #[allow(dead_code)]
+    // Constructor for the sRGB gray with CIE lightness `l_star` (clamped to
+    // [0, 100]). L* is perceptual, so L* 50 is #777777, darker than the
+    // 50% gray #808080.
+    pub fn from_perceptual_lightness(l_star: f32) -> Self {
+        let y = lab_f_inv((l_star.clamp(0.0, 100.0) + 16.0) / 116.0);
+        let level = to_byte(linear_to_srgb(y));
+        Self::from_rgb(level, level, level)
+    }
+
+    // This color moved to CIE lightness `l_star` (clamped to [0, 100])
+    // keeping its Lab hue and chroma. Where that falls outside sRGB the
+    // chroma is reduced just enough to fit, so lightness and hue still hold.
+    pub fn set_perceptual_lightness(&self, l_star: f32) -> Color {
+        let l = l_star.clamp(0.0, 100.0);
+        let (_, a, b) = self.to_lab();
+        let fits = |scale: f32| {
+            xyz_to_linear_srgb(lab_to_xyz(l, a * scale, b * scale, WhitePoint::D65))
+                .iter()
+                .all(|c| (-1e-4..=1.0 + 1e-4).contains(c))
+        };
+        let mut scale = 1.0;
+        if !fits(scale) {
+            // Grays always fit, so search between no chroma and the original
+            let (mut low, mut high) = (0.0, 1.0);
+            for _ in 0..24 {
+                let mid = (low + high) / 2.0;
+                if fits(mid) {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            scale = low;
+        }
+
+        // Plain rounding to 8 bits can be off by a few tenths of L*, so
+        // take whichever nearby byte triple is closest, weighting lightness
+        // errors well above hue and chroma ones
+        let (a, b) = (a * scale, b * scale);
+        let encoded = xyz_to_linear_srgb(lab_to_xyz(l, a, b, WhitePoint::D65))
+            .map(|c| linear_to_srgb(c).clamp(0.0, 1.0) * 255.0);
+        let cost = |color: &Color| {
+            let (cl, ca, cb) = color.to_lab();
+            (10.0 * (cl - l)).powi(2) + (ca - a).powi(2) + (cb - b).powi(2)
+        };
+        // Each channel ranges over the two bytes either side of its value
+        (0..64)
+            .map(|corner| {
+                let channel = |i: usize| {
+                    let offset = (corner >> (2 * i) & 3) as f32 - 1.0;
+                    (encoded[i].floor() + offset).clamp(0.0, 255.0) as u8
+                };
+                Color::from_rgb(channel(0), channel(1), channel(2))
+            })
+            .min_by(|x, y| cost(x).total_cmp(&cost(y)))
+            .unwrap_or_else(|| Self::from_lab(l, a, b))
+    }
+
+    // Constructor from CIELAB relative to the given reference white
+    pub fn from_lab_with_white(l: f32, a: f32, b: f32, white: WhitePoint) -> Self {
+        Self::from_xyz(lab_to_xyz(l, a, b, white))
+    }
+
+    // Convert to display-p3 components in [0, 1]. Every sRGB color is
+    // inside P3, so nothing is clipped.
+    pub fn to_display_p3(&self) -> [f32; 3] {
+        mul(&XYZ_TO_DISPLAY_P3, self.to_xyz()).map(|c| linear_to_srgb(c).clamp(0.0, 1.0))
+    }
+
+    // Convert to ProPhoto (ROMM) RGB components in [0, 1]. ProPhoto is
+    // defined against D50, so the D65 sRGB value is Bradford-adapted first.
+    pub fn to_prophoto(&self) -> (f32, f32, f32) {
+        let xyz = bradford_adapt(self.to_xyz(), WhitePoint::D65, WhitePoint::D50);
+        let [r, g, b] = mul(&XYZ_TO_PROPHOTO, xyz);
+        let encode = |c: f32| {
+            let c = c.clamp(0.0, 1.0);
+            if c < 1.0 / 512.0 {
+                c * 16.0
+            } else {
+                c.powf(1.0 / 1.8)
+            }
+        };
+        (encode(r), encode(g), encode(b))
+    }
+
+    #[allow(dead_code)]
+    // Constructor from ProPhoto (ROMM) RGB components in [0, 1]
+    pub fn from_prophoto(r: f32, g: f32, b: f32) -> Self {
+        let decode = |c: f32| {
+            if c < 16.0 / 512.0 {
+                c / 16.0
+            } else {
+                c.powf(1.8)
+            }
+        };
+        let xyz = mul(&PROPHOTO_TO_XYZ, [decode(r), decode(g), decode(b)]);
+        Self::from_xyz(bradford_adapt(xyz, WhitePoint::D50, WhitePoint::D65))
+    }
+
+    #[allow(dead_code)]
+    // Map a color seen under one white point to the corresponding color
+    // under another (Bradford). The same white on both sides is an identity.
+    pub fn adapt_white_point(&self, from: WhitePoint, to: WhitePoint) -> Color {
+        if from == to {
+            return self.clone();
+        }
+        Self::from_xyz(bradford_adapt(self.to_xyz(), from, to))
+    }
+
+    // Perceptual distance to another color (CIEDE2000, D65 Lab)
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        let (l1, a1, b1) = self.to_lab_with_white(WhitePoint::D65);
+        let (l2, a2, b2) = other.to_lab_with_white(WhitePoint::D65);
+        let (l1, a1, b1) = (f64::from(l1), f64::from(a1), f64::from(b1));
+        let (l2, a2, b2) = (f64::from(l2), f64::from(a2), f64::from(b2));
+
+        let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+        let c_bar7 = c_bar.powi(7);
+        let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+        let a1p = a1 * (1.0 + g);
+        let a2p = a2 * (1.0 + g);
+        let c1p = a1p.hypot(b1);
+        let c2p = a2p.hypot(b2);
+        let hue = |b: f64, a: f64| {
+            if a == 0.0 && b == 0.0 {
+                0.0
+            } else {
+                b.atan2(a).to_degrees().rem_euclid(360.0)
+            }
+        };
+        let h1p = hue(b1, a1p);
+        let h2p = hue(b2, a2p);
+
+        let dl = l2 - l1;
+        let dc = c2p - c1p;
+        let dh = if c1p * c2p == 0.0 {
+            0.0
+        } else if (h2p - h1p).abs() <= 180.0 {
+            h2p - h1p
+        } else if h2p - h1p > 180.0 {
+            h2p - h1p - 360.0
+        } else {
+            h2p - h1p + 360.0
+        };
+        let dh_big = 2.0 * (c1p * c2p).sqrt() * (dh.to_radians() / 2.0).sin();
+
+        let l_bar = (l1 + l2) / 2.0;
+        let c_bar_p = (c1p + c2p) / 2.0;
+        let h_bar = if c1p * c2p == 0.0 {
+            h1p + h2p
+        } else if (h1p - h2p).abs() <= 180.0 {
+            (h1p + h2p) / 2.0
+        } else if h1p + h2p < 360.0 {
+            (h1p + h2p + 360.0) / 2.0
+        } else {
+            (h1p + h2p - 360.0) / 2.0
+        };
+
+        let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+            + 0.24 * (2.0 * h_bar).to_radians().cos()
+            + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+            - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+        let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let c_bar_p7 = c_bar_p.powi(7);
+        let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + 25f64.powi(7))).sqrt();
+        let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar_p;
+        let s_h = 1.0 + 0.015 * c_bar_p * t;
+        let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+        let dl = dl / s_l;
+        let dc = dc / s_c;
+        let dh = dh_big / s_h;
+        (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt() as f32
+    }
+}
+
+// Oklab (Björn Ottosson, 2020) from linear-light sRGB
+pub fn linear_srgb_to_oklab(rgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb;
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+// Linear-light sRGB from Oklab, unclipped
+pub fn oklab_to_linear_srgb(lab: [f32; 3]) -> [f32; 3] {
+    let [l, a, b] = lab;
+    let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+    let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+    let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+    [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ]
+}
+
+impl Color {
+    // Convert to Oklab (L in [0, 1])
+    pub fn to_oklab(&self) -> (f32, f32, f32) {
+        let [l, a, b] = linear_srgb_to_oklab(self.to_linear_rgb());
+        (l, a, b)
+    }
+
+    // Constructor from Oklab, clipped to the sRGB gamut
+    pub fn from_oklab(l: f32, a: f32, b: f32) -> Self {
+        Self::from_linear_rgb(oklab_to_linear_srgb([l, a, b]))
+    }
+
+    // Convert to OKLCH, with the hue in degrees
+    pub fn to_oklch(&self) -> (f32, f32, f32) {
+        let (l, a, b) = self.to_oklab();
+        (l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+    }
+
+    // Constructor from OKLCH, with the hue in degrees
+    pub fn from_oklch(l: f32, c: f32, h: f32) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+        Self::from_oklab(l, c * cos, c * sin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_xyz_close(got: [f32; 3], expected: [f32; 3]) {
+        for (g, e) in got.iter().zip(expected) {
+            assert!((g - e).abs() < 2e-4, "{got:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn bradford_matches_published_d65_to_d50_values() {
+        // Columns of Lindbloom's Bradford-adapted sRGB (D50) matrix
+        assert_xyz_close(
+            bradford_adapt(
+                Color::from_rgb(255, 0, 0).to_xyz(),
+                WhitePoint::D65,
+                WhitePoint::D50,
+            ),
+            [0.436_074_7, 0.222_504_5, 0.013_932_2],
+        );
+        assert_xyz_close(
+            bradford_adapt(
+                Color::from_rgb(0, 255, 0).to_xyz(),
+                WhitePoint::D65,
+                WhitePoint::D50,
+            ),
+            [0.385_064_9, 0.716_878_6, 0.097_104_5],
+        );
+        assert_xyz_close(
+            bradford_adapt(
+                Color::from_rgb(0, 0, 255).to_xyz(),
+                WhitePoint::D65,
+                WhitePoint::D50,
+            ),
+            [0.143_080_4, 0.060_616_9, 0.714_173_3],
+        );
+        assert_xyz_close(
+            bradford_adapt(WhitePoint::D65.xyz(), WhitePoint::D65, WhitePoint::D50),
+            WhitePoint::D50.xyz(),
+        );
+    }
+
+    #[test]
+    fn adapting_to_the_same_white_is_an_exact_identity() {
+        let whites = [
+            WhitePoint::D50,
+            WhitePoint::D65,
+            WhitePoint::A,
+            WhitePoint::E,
+            WhitePoint::Custom { x: 0.3, y: 0.32 },
+        ];
+        let xyz = [0.123_456_7, 0.234_567_8, 0.345_678_9];
+        for white in whites {
+            assert_eq!(bradford_adapt(xyz, white, white), xyz);
+            for color in [
+                Color::from_rgb(0, 0, 0),
+                Color::from_rgb(255, 255, 255),
+                Color::from_rgb(12, 200, 77),
+            ] {
+                assert_eq!(color.adapt_white_point(white, white), color);
+            }
+        }
+    }
+
+    #[test]
+    fn perceptual_lightness_50_is_not_50_percent_gray() {
+        let gray = Color::from_perceptual_lightness(50.0);
+        assert_eq!(gray, Color::from_rgb(0x77, 0x77, 0x77));
+        let (l, _, _) = gray.to_lab();
+        assert!((l - 50.0).abs() < 0.1, "{l}");
+        // #BCBCBC, sometimes quoted as L* 50, is much lighter
+        let (l, _, _) = Color::from_rgb(0xBC, 0xBC, 0xBC).to_lab();
+        assert!((l - 76.0).abs() < 0.5, "{l}");
+    }
+
+    #[test]
+    fn perceptual_lightness_covers_black_to_white() {
+        assert_eq!(
+            Color::from_perceptual_lightness(0.0),
+            Color::from_rgb(0, 0, 0)
+        );
+        assert_eq!(
+            Color::from_perceptual_lightness(100.0),
+            Color::from_rgb(255, 255, 255)
+        );
+        assert_eq!(
+            Color::from_perceptual_lightness(-5.0),
+            Color::from_rgb(0, 0, 0)
+        );
+        assert_eq!(
+            Color::from_perceptual_lightness(150.0),
+            Color::from_rgb(255, 255, 255)
+        );
+        for l_star in (0..=100).step_by(5) {
+            let gray = Color::from_perceptual_lightness(l_star as f32);
+            let (l, _, _) = gray.to_lab();
+            // One 8-bit step is at most about 0.4 L* near black
+            assert!((l - l_star as f32).abs() < 0.5, "L* {l_star} gave {l}");
+        }
+    }
+
+    // Lab lightness, chroma and hue in degrees
+    fn lch(color: &Color) -> (f32, f32, f32) {
+        let (l, a, b) = color.to_lab();
+        (l, a.hypot(b), b.atan2(a).to_degrees())
+    }
+
+    #[test]
+    fn set_perceptual_lightness_hits_the_target_keeping_hue_and_chroma() {
+        let cases = [
+            (Color::from_rgb(0x33, 0x66, 0x99), 40..=70),
+            (Color::from_rgb(0xC8, 0x50, 0x3C), 30..=60),
+            (Color::from_rgb(0x7F, 0x3F, 0xBF), 20..=60),
+        ];
+        for (color, targets) in cases {
+            let (_, chroma, hue) = lch(&color);
+            for target in targets.step_by(10) {
+                let target = target as f32;
+                let moved = color.set_perceptual_lightness(target);
+                let (l, c, h) = lch(&moved);
+                assert!((l - target).abs() <= 0.1, "{color:?} to {target}: L* {l}");
+                // As close as 8-bit channels allow; at these chromas one
+                // byte moves the hue by up to about a degree
+                assert!(
+                    (c - chroma).abs() < 0.5,
+                    "{color:?} to {target}: C {c} vs {chroma}"
+                );
+                assert!(
+                    (h - hue).abs() < 1.5,
+                    "{color:?} to {target}: h {h} vs {hue}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn set_perceptual_lightness_reduces_chroma_only_outside_srgb() {
+        // Rust at L* 90 does not fit in sRGB at its full chroma
+        let color = Color::from_rgb(0xC8, 0x50, 0x3C);
+        let (_, chroma, hue) = lch(&color);
+        let (l, c, h) = lch(&color.set_perceptual_lightness(90.0));
+        assert!((l - 90.0).abs() <= 0.1, "{l}");
+        assert!(c < chroma / 2.0, "{c}");
+        assert!((h - hue).abs() < 2.5, "{h} vs {hue}");
+    }
+
+    #[test]
+    fn set_perceptual_lightness_stays_within_one_byte_step() {
+        // Where 8-bit rounding leaves no closer triple the target can be
+        // missed by up to about 0.2 L*, never more
+        for color in crate::palette::web_safe_palette() {
+            for target in 0..=100 {
+                let target = target as f32;
+                let (l, _, _) = color.set_perceptual_lightness(target).to_lab();
+                assert!((l - target).abs() < 0.2, "{color:?} to {target}: L* {l}");
+            }
+        }
+        let (l, c, _) = lch(&Color::from_rgb(120, 120, 120).set_perceptual_lightness(30.0));
+        assert!((l - 30.0).abs() <= 0.1 && c < 1.5, "L* {l}, C {c}");
+        assert_eq!(
+            Color::from_rgb(0x33, 0x66, 0x99).set_perceptual_lightness(150.0),
+            Color::from_rgb(255, 255, 255)
+        );
+    }
+}
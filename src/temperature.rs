@@ -0,0 +1,107 @@
+use crate::space::xyz_to_linear_srgb;
+use crate::Color;
+
+// Range of the Krystek approximation of the Planckian locus
+const MIN_KELVIN: f32 = 1000.0;
+const MAX_KELVIN: f32 = 15000.0;
+
+// Beyond this distance from the locus a CCT stops being meaningful
+const MAX_DUV: f32 = 0.05;
+
+// CIE 1960 (u, v) of a black body at the given temperature (Krystek, 1985)
+fn planckian_uv(kelvin: f32) -> (f32, f32) {
+    let t = f64::from(kelvin);
+    let u = (0.860_117_757 + 1.541_182_54e-4 * t + 1.286_412_12e-7 * t * t)
+        / (1.0 + 8.424_202_35e-4 * t + 7.081_451_63e-7 * t * t);
+    let v = (0.317_398_726 + 4.228_062_45e-5 * t + 4.204_816_91e-8 * t * t)
+        / (1.0 - 2.897_418_16e-5 * t + 1.614_560_53e-7 * t * t);
+    (u as f32, v as f32)
+}
+
+impl Color {
+    #[allow(dead_code)]
+    // Constructor for the color of a black body at the given temperature,
+    // scaled so its brightest channel is at full intensity
+    pub fn from_temperature(kelvin: f32) -> Self {
+        let (u, v) = planckian_uv(kelvin.clamp(MIN_KELVIN, MAX_KELVIN));
+        let denom = 2.0 * u - 8.0 * v + 4.0;
+        let x = 3.0 * u / denom;
+        let y = 2.0 * v / denom;
+
+        let rgb = xyz_to_linear_srgb([x / y, 1.0, (1.0 - x - y) / y]);
+        let peak = rgb[0].max(rgb[1]).max(rgb[2]);
+        Self::from_linear_rgb(rgb.map(|c| c.max(0.0) / peak))
+    }
+
+    // Correlated color temperature in Kelvin (McCamy's approximation) and
+    // the signed Duv distance from the Planckian locus. None for black or
+    // for colors too far from the locus for a CCT to mean anything.
+    pub fn cct_duv(&self) -> Option<(f32, f32)> {
+        let [x_t, y_t, z_t] = self.to_xyz();
+        let sum = x_t + y_t + z_t;
+        if sum <= 0.0 {
+            return None;
+        }
+        let x = x_t / sum;
+        let y = y_t / sum;
+
+        let n = (x - 0.3320) / (y - 0.1858);
+        let cct = -449.0 * n.powi(3) + 3525.0 * n.powi(2) - 6823.3 * n + 5520.33;
+        if !(MIN_KELVIN..=MAX_KELVIN).contains(&cct) {
+            return None;
+        }
+
+        let denom = -2.0 * x + 12.0 * y + 3.0;
+        let u = 4.0 * x / denom;
+        let v = 6.0 * y / denom;
+        let (u_locus, v_locus) = planckian_uv(cct);
+        let distance = (u - u_locus).hypot(v - v_locus);
+        let duv = if v >= v_locus { distance } else { -distance };
+
+        (duv.abs() <= MAX_DUV).then_some((cct, duv))
+    }
+
+    #[allow(dead_code)]
+    // Correlated color temperature in Kelvin, see `cct_duv`
+    pub fn cct(&self) -> Option<f32> {
+        self.cct_duv().map(|(cct, _)| cct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cct_recovers_black_body_temperatures() {
+        for kelvin in [2700.0, 4000.0, 5000.0, 6500.0] {
+            let cct = Color::from_temperature(kelvin).cct().unwrap();
+            // 8-bit quantization and McCamy's fit both cost some accuracy
+            assert!(
+                (cct - kelvin).abs() < kelvin * 0.02,
+                "{kelvin} K came back as {cct} K"
+            );
+        }
+    }
+
+    #[test]
+    fn cct_is_near_the_locus_for_black_bodies() {
+        let (_, duv) = Color::from_temperature(6500.0).cct_duv().unwrap();
+        assert!(duv.abs() < 0.005, "Duv {duv}");
+    }
+
+    #[test]
+    fn cct_is_none_off_the_locus_and_for_black() {
+        assert_eq!(Color { r: 0, g: 255, b: 0 }.cct(), None);
+        assert_eq!(
+            Color {
+                r: 255,
+                g: 0,
+                b: 255
+            }
+            .cct(),
+            None
+        );
+        assert_eq!(Color { r: 0, g: 0, b: 0 }.cct(), None);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::Color;
+
+// Build a sixel stream that draws a solid `width` x `height` rectangle of
+// the color. Sixel packs six pixel rows per character, so the last band
+// only sets the bits for the rows that are left.
+pub fn color_to_sixel_swatch(color: &Color, width: u32, height: u32) -> String {
+    let percent = |c: u8| (f32::from(c) * 100.0 / 255.0).round() as u8;
+
+    // DCS introducer and raster attributes (1:1 aspect, exact size)
+    let mut out = format!("\x1bPq\"1;1;{width};{height}");
+    out.push_str(&format!(
+        "#0;2;{};{};{}",
+        percent(color.r),
+        percent(color.g),
+        percent(color.b)
+    ));
+
+    if width > 0 {
+        let mut remaining = height;
+        while remaining > 0 {
+            let rows = remaining.min(6);
+            let sixel = char::from(63 + ((1u8 << rows) - 1));
+            out.push_str(&format!("#0!{width}{sixel}"));
+            remaining -= rows;
+            if remaining > 0 {
+                out.push('-');
+            }
+        }
+    }
+
+    // String terminator
+    out.push_str("\x1b\\");
+    out
+}
+
+impl Color {
+    // SGR 58: the underline color, understood by kitty, VTE and others
+    pub fn to_kitty_osc_underline_color(&self) -> String {
+        format!("\x1b[58;2;{};{};{}m", self.r, self.g, self.b)
+    }
+
+    // OSC 11: set the terminal's default background, ended by ST
+    pub fn to_kitty_bg_color(&self) -> String {
+        format!("\x1b]11;{}\x1b\\", self.to_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sixel_swatch_is_wrapped_in_dcs_and_st() {
+        let sixel = color_to_sixel_swatch(&Color { r: 255, g: 0, b: 0 }, 4, 8);
+        assert!(sixel.starts_with("\x1bP"));
+        assert!(sixel.ends_with("\x1b\\"));
+        assert_eq!(sixel, "\x1bPq\"1;1;4;8#0;2;100;0;0#0!4~-#0!4B\x1b\\");
+    }
+
+    #[test]
+    fn sixel_swatch_handles_empty_rectangles() {
+        let color = Color { r: 0, g: 0, b: 255 };
+        for (width, height) in [(0, 6), (6, 0)] {
+            let sixel = color_to_sixel_swatch(&color, width, height);
+            assert!(sixel.starts_with("\x1bP"));
+            assert!(sixel.ends_with("\x1b\\"));
+            assert!(!sixel.contains("#0!"));
+        }
+    }
+
+    #[test]
+    fn kitty_underline_color_uses_sgr_58() {
+        let color = Color {
+            r: 255,
+            g: 128,
+            b: 0,
+        };
+        assert_eq!(color.to_kitty_osc_underline_color(), "\x1b[58;2;255;128;0m");
+    }
+
+    #[test]
+    fn kitty_background_uses_osc_11_ended_by_st() {
+        let color = Color {
+            r: 255,
+            g: 128,
+            b: 0,
+        };
+        let osc = color.to_kitty_bg_color();
+        assert_eq!(osc, "\x1b]11;#FF8000\x1b\\");
+        let body = osc
+            .strip_prefix("\x1b]")
+            .and_then(|s| s.strip_suffix("\x1b\\"))
+            .unwrap();
+        assert_eq!(body.split_once(';'), Some(("11", "#FF8000")));
+    }
+}
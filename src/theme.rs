@@ -0,0 +1,567 @@
+use crate::a11y::{self, WcagLevel};
+use crate::json;
+use crate::Color;
+
+// Colors a UI theme is derived from, all sharing the base hue
+struct ThemeColors {
+    background: Color,
+    foreground: Color,
+    accent: Color,
+    selection: Color,
+    selection_foreground: Color,
+    highlight: Color,
+}
+
+impl ThemeColors {
+    fn from_base(base: &Color, is_dark: bool) -> Self {
+        let (h, s, _) = base.to_hsl();
+        // Keep the accent clearly colored even for a washed-out base
+        let accent_s = s.max(0.5);
+        let hsl = |s: f32, l: f32| Color::from_hsl(h, s, l);
+        if is_dark {
+            ThemeColors {
+                background: hsl(0.1, 0.15),
+                foreground: hsl(0.05, 0.85),
+                accent: hsl(accent_s, 0.6),
+                selection: hsl(accent_s * 0.6, 0.3),
+                selection_foreground: hsl(0.05, 0.95),
+                highlight: hsl(accent_s, 0.35),
+            }
+        } else {
+            ThemeColors {
+                background: hsl(0.1, 0.96),
+                foreground: hsl(0.1, 0.15),
+                accent: hsl(accent_s, 0.4),
+                selection: hsl(accent_s * 0.6, 0.82),
+                selection_foreground: hsl(0.1, 0.1),
+                highlight: hsl(accent_s, 0.75),
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+// A minimal JetBrains IDE theme (`.theme.json`) whose background,
+// foreground, accent, selection and search highlight colors all take the
+// hue of `base`
+pub fn generate_jetbrains_theme(name: &str, is_dark: bool, base: &Color) -> String {
+    let colors = ThemeColors::from_base(base, is_dark);
+    let [background, foreground, accent, selection, selection_foreground, highlight] = [
+        &colors.background,
+        &colors.foreground,
+        &colors.accent,
+        &colors.selection,
+        &colors.selection_foreground,
+        &colors.highlight,
+    ]
+    .map(|c| json::quote(&c.to_hex()));
+    format!(
+        r#"{{
+  "name": {},
+  "dark": {is_dark},
+  "author": "chromatic",
+  "ui": {{
+    "*": {{
+      "background": {background},
+      "foreground": {foreground},
+      "selectionBackground": {selection},
+      "selectionForeground": {selection_foreground},
+      "selectionInactiveBackground": {selection}
+    }},
+    "Component.focusColor": {accent},
+    "Link.activeForeground": {accent},
+    "Button.default.startBackground": {accent},
+    "Button.default.endBackground": {accent},
+    "EditorTabs.underlineColor": {accent},
+    "SearchMatch.startBackground": {highlight},
+    "SearchMatch.endBackground": {highlight}
+  }}
+}}
+"#,
+        json::quote(name)
+    )
+}
+
+// Names of the eight ANSI colors in palette order
+pub const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// ANSI hues in palette order: red, green, yellow, blue, magenta, cyan
+const ANSI_HUES: [f32; 6] = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+
+// A 16-color terminal palette in ANSI order (black, red, green, yellow,
+// blue, magenta, cyan, white, then the bright variants)
+pub struct TerminalScheme {
+    pub palette: [Color; 16],
+    pub foreground: Color,
+    pub background: Color,
+}
+
+impl TerminalScheme {
+    // The six hues keep their usual positions and take the saturation of
+    // `base`, while black, white and the default colors take its hue
+    pub fn from_base(base: &Color, is_dark: bool) -> Self {
+        let (h, s, _) = base.to_hsl();
+        let colors = ThemeColors::from_base(base, is_dark);
+        let s = s.clamp(0.4, 0.8);
+        let (normal_l, bright_l) = if is_dark { (0.55, 0.7) } else { (0.4, 0.55) };
+        let palette = std::array::from_fn(|i| {
+            let bright = i >= 8;
+            match i % 8 {
+                0 => Color::from_hsl(h, 0.1, if bright { 0.35 } else { 0.12 }),
+                7 => Color::from_hsl(h, 0.05, if bright { 0.97 } else { 0.8 }),
+                slot => Color::from_hsl(
+                    ANSI_HUES[slot - 1],
+                    s,
+                    if bright { bright_l } else { normal_l },
+                ),
+            }
+        });
+        TerminalScheme {
+            palette,
+            foreground: colors.foreground,
+            background: colors.background,
+        }
+    }
+}
+
+// 128-bit FNV-1a hash of `name` formatted as a version 4 UUID, so the same
+// scheme name always updates the same profile
+fn profile_uuid(name: &str) -> String {
+    let mut hash: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    for byte in name.bytes() {
+        hash ^= u128::from(byte);
+        hash = hash.wrapping_mul(0x0000_0000_0100_0000_0000_0000_0000_013b);
+    }
+    hash = hash & !(0xf << 76) | 0x4 << 76;
+    hash = hash & !(0x3 << 62) | 0x2 << 62;
+    let hex = format!("{hash:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+// Quote text as a GVariant string inside a single-quoted shell word
+fn shell_gvariant_string(text: &str) -> String {
+    let gvariant = format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"));
+    format!("'{}'", gvariant.replace('\'', "'\\''"))
+}
+
+// A shell script of `gsettings set` commands that creates (or updates) a
+// GNOME Terminal profile called `name` with the given colors and adds it to
+// the profile list
+pub fn generate_gnome_terminal_profile(
+    name: &str,
+    palette: &[Color; 16],
+    fg: &Color,
+    bg: &Color,
+) -> String {
+    let uuid = profile_uuid(name);
+    let entries: Vec<String> = palette
+        .iter()
+        .map(|c| format!("'{}'", c.to_hex()))
+        .collect();
+    format!(
+        r#"#!/bin/sh
+# GNOME Terminal profile generated by chromatic
+PROFILE="org.gnome.Terminal.Legacy.Profile:/org/gnome/terminal/legacy/profiles:/:{uuid}/"
+LIST=$(gsettings get org.gnome.Terminal.ProfilesList list)
+case "$LIST" in
+  *"'{uuid}'"*) ;;
+  "@as []") gsettings set org.gnome.Terminal.ProfilesList list "['{uuid}']" ;;
+  *) gsettings set org.gnome.Terminal.ProfilesList list "${{LIST%]}}, '{uuid}']" ;;
+esac
+gsettings set "$PROFILE" visible-name {}
+gsettings set "$PROFILE" use-theme-colors false
+gsettings set "$PROFILE" palette "[{}]"
+gsettings set "$PROFILE" foreground-color '{}'
+gsettings set "$PROFILE" background-color '{}'
+"#,
+        shell_gvariant_string(name),
+        entries.join(", "),
+        fg.to_hex(),
+        bg.to_hex()
+    )
+}
+
+// Names of the primary scale steps, lightest first
+pub const SCALE_STEPS: [u16; 10] = [50, 100, 200, 300, 400, 500, 600, 700, 800, 900];
+
+// HSL lightness of each primary scale step
+const SCALE_LIGHTNESS: [f32; 10] = [0.95, 0.9, 0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1];
+
+// A design system starter derived from one brand color
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrandingKit {
+    pub primary: Color,
+    // Complement of the primary
+    pub secondary: Color,
+    // Second triadic hue of the primary
+    pub tertiary: Color,
+    // The primary and its three tetradic hues
+    pub accents: [Color; 4],
+    // The primary hue and saturation at each of `SCALE_STEPS`
+    pub scale: [Color; 10],
+    pub background_light: Color,
+    pub background_dark: Color,
+    // Passes WCAG AA on `background_light`
+    pub text_on_light: Color,
+    // Passes WCAG AA on `background_dark`
+    pub text_on_dark: Color,
+}
+
+// A `BrandingKit` for `primary`. The backgrounds are near-white and
+// near-black tints of its hue, and the text colors keep that hue too.
+pub fn generate_branding_kit(primary: &Color) -> BrandingKit {
+    let (h, s, _) = primary.to_hsl();
+    let [_, tertiary] = primary.triadic();
+    let [square_1, secondary, square_3] = primary.tetradic();
+    let background_light = Color::from_hsl(h, s.min(0.2), 0.97);
+    let background_dark = Color::from_hsl(h, s.min(0.2), 0.1);
+    let text_s = s.min(0.3);
+    BrandingKit {
+        primary: primary.clone(),
+        tertiary,
+        accents: [primary.clone(), square_1, secondary.clone(), square_3],
+        secondary,
+        scale: SCALE_LIGHTNESS.map(|l| Color::from_hsl(h, s, l)),
+        text_on_light: a11y::text_in_hue_for_background(
+            h,
+            text_s,
+            &background_light,
+            WcagLevel::Aa,
+        ),
+        text_on_dark: a11y::text_in_hue_for_background(h, text_s, &background_dark, WcagLevel::Aa),
+        background_light,
+        background_dark,
+    }
+}
+
+impl BrandingKit {
+    // Every color as a `--brand-*` custom property on `:root`
+    pub fn to_css_vars(&self) -> String {
+        let mut vars = vec![
+            ("primary".to_string(), &self.primary),
+            ("secondary".to_string(), &self.secondary),
+            ("tertiary".to_string(), &self.tertiary),
+        ];
+        for (i, accent) in self.accents.iter().enumerate() {
+            vars.push((format!("accent-{}", i + 1), accent));
+        }
+        for (step, color) in SCALE_STEPS.iter().zip(&self.scale) {
+            vars.push((format!("primary-{step}"), color));
+        }
+        vars.push(("background-light".to_string(), &self.background_light));
+        vars.push(("background-dark".to_string(), &self.background_dark));
+        vars.push(("text-on-light".to_string(), &self.text_on_light));
+        vars.push(("text-on-dark".to_string(), &self.text_on_dark));
+
+        let mut out = String::from(":root {\n");
+        for (name, color) in vars {
+            out.push_str(&format!("  --brand-{name}: {};\n", color.to_hex()));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+// The `[colors]` table of an Alacritty TOML config: primary foreground and
+// background plus the normal and bright palettes, as lowercase hex
+pub fn generate_alacritty_colors(palette_16: &[Color; 16], fg: &Color, bg: &Color) -> String {
+    let hex = |c: &Color| c.to_hex().to_ascii_lowercase();
+    let mut out = String::from("[colors]\n");
+    out.push_str(&format!("primary.background = \"{}\"\n", hex(bg)));
+    out.push_str(&format!("primary.foreground = \"{}\"\n", hex(fg)));
+    for (group, colors) in [("normal", &palette_16[..8]), ("bright", &palette_16[8..])] {
+        for (name, color) in ANSI_NAMES.iter().zip(colors) {
+            out.push_str(&format!("{group}.{name} = \"{}\"\n", hex(color)));
+        }
+    }
+    out
+}
+
+// A Windows Terminal color scheme object for the `schemes` array of
+// settings.json. The selection uses the bright black palette entry, and
+// ANSI magenta goes by `purple` as Windows Terminal names it.
+pub fn generate_windows_terminal_scheme(
+    name: &str,
+    palette: &[Color; 16],
+    fg: &Color,
+    bg: &Color,
+    cursor: &Color,
+) -> String {
+    let mut members = vec![
+        ("name".to_string(), json::quote(name)),
+        ("background".to_string(), json::quote(&bg.to_hex())),
+        ("foreground".to_string(), json::quote(&fg.to_hex())),
+        ("cursorColor".to_string(), json::quote(&cursor.to_hex())),
+        (
+            "selectionBackground".to_string(),
+            json::quote(&palette[8].to_hex()),
+        ),
+    ];
+    for (i, color) in palette.iter().enumerate() {
+        let base = match ANSI_NAMES[i % 8] {
+            "magenta" => "purple",
+            name => name,
+        };
+        let key = if i < 8 {
+            base.to_string()
+        } else {
+            format!("bright{}{}", base[..1].to_ascii_uppercase(), &base[1..])
+        };
+        members.push((key, json::quote(&color.to_hex())));
+    }
+    let body: Vec<String> = members
+        .iter()
+        .map(|(key, value)| format!("  \"{key}\": {value}"))
+        .collect();
+    format!("{{\n{}\n}}\n", body.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_terminal_scheme_has_the_required_keys() {
+        let scheme = TerminalScheme::from_base(&Color::from_rgb(40, 120, 200), true);
+        let output = generate_windows_terminal_scheme(
+            "Test \"scheme\"",
+            &scheme.palette,
+            &scheme.foreground,
+            &scheme.background,
+            &Color::from_rgb(255, 255, 255),
+        );
+        let document = json::parse(&output).unwrap();
+        let json::Json::Object(members) = &document else {
+            panic!("scheme is not a JSON object: {output}");
+        };
+
+        let required = [
+            "background",
+            "foreground",
+            "cursorColor",
+            "selectionBackground",
+            "black",
+            "red",
+            "green",
+            "yellow",
+            "blue",
+            "purple",
+            "cyan",
+            "white",
+            "brightBlack",
+            "brightRed",
+            "brightGreen",
+            "brightYellow",
+            "brightBlue",
+            "brightPurple",
+            "brightCyan",
+            "brightWhite",
+        ];
+        let mut colors: Vec<&str> = members
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .filter(|key| *key != "name")
+            .collect();
+        let mut expected = required.to_vec();
+        colors.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(colors, expected);
+
+        assert_eq!(
+            document.get("name").and_then(json::Json::as_str),
+            Some("Test \"scheme\"")
+        );
+        for key in required {
+            let hex = document.get(key).and_then(json::Json::as_str).unwrap();
+            assert!(Color::from_hex(hex).is_ok(), "{key}: {hex}");
+        }
+        assert_eq!(
+            document.get("purple").and_then(json::Json::as_str),
+            Some(scheme.palette[5].to_hex().as_str())
+        );
+    }
+
+    #[test]
+    fn jetbrains_theme_has_the_required_top_level_keys() {
+        for is_dark in [true, false] {
+            let output =
+                generate_jetbrains_theme("Ocean \"Blue\"", is_dark, &Color::from_rgb(40, 120, 200));
+            let document = json::parse(&output).unwrap();
+            let json::Json::Object(members) = &document else {
+                panic!("theme is not a JSON object: {output}");
+            };
+            let keys: Vec<&str> = members.iter().map(|(key, _)| key.as_str()).collect();
+            assert_eq!(keys, ["name", "dark", "author", "ui"]);
+            assert_eq!(
+                document.get("name").and_then(json::Json::as_str),
+                Some("Ocean \"Blue\"")
+            );
+            assert_eq!(document.get("dark"), Some(&json::Json::Bool(is_dark)));
+            assert!(document
+                .get("author")
+                .and_then(json::Json::as_str)
+                .is_some());
+
+            let ui = document.get("ui").unwrap();
+            let json::Json::Object(ui_members) = ui else {
+                panic!("ui is not an object");
+            };
+            let defaults = ui.get("*").unwrap();
+            for key in ["background", "foreground", "selectionBackground"] {
+                let hex = defaults.get(key).and_then(json::Json::as_str).unwrap();
+                assert!(Color::from_hex(hex).is_ok(), "{key}: {hex}");
+            }
+            for (key, value) in ui_members.iter().filter(|(key, _)| key != "*") {
+                let hex = value.as_str().unwrap();
+                assert!(Color::from_hex(hex).is_ok(), "{key}: {hex}");
+            }
+        }
+    }
+
+    #[test]
+    fn gnome_profile_sets_16_palette_entries_and_default_colors() {
+        let scheme = TerminalScheme::from_base(&Color::from_rgb(40, 120, 200), true);
+        let script = generate_gnome_terminal_profile(
+            "Ocean",
+            &scheme.palette,
+            &scheme.foreground,
+            &scheme.background,
+        );
+        let setting = |key: &str| {
+            let prefix = format!("gsettings set \"$PROFILE\" {key} ");
+            let lines: Vec<&str> = script
+                .lines()
+                .filter_map(|line| line.strip_prefix(prefix.as_str()))
+                .collect();
+            assert_eq!(lines.len(), 1, "{key} in {script}");
+            lines[0]
+        };
+
+        let palette = setting("palette")
+            .strip_prefix("\"[")
+            .and_then(|p| p.strip_suffix("]\""))
+            .unwrap();
+        let entries: Vec<&str> = palette.split(", ").collect();
+        assert_eq!(entries.len(), 16);
+        for (entry, color) in entries.iter().zip(&scheme.palette) {
+            assert_eq!(*entry, format!("'{}'", color.to_hex()));
+        }
+        assert_eq!(
+            setting("foreground-color"),
+            format!("'{}'", scheme.foreground.to_hex())
+        );
+        assert_eq!(
+            setting("background-color"),
+            format!("'{}'", scheme.background.to_hex())
+        );
+        assert_eq!(setting("use-theme-colors"), "false");
+        // The shell word '' \' 'Ocean' \' '' passes the GVariant 'Ocean'
+        assert_eq!(setting("visible-name"), r#"''\''Ocean'\'''"#);
+    }
+
+    #[test]
+    fn gnome_profile_ids_are_stable_v4_uuids() {
+        let uuid = profile_uuid("Ocean");
+        assert_eq!(uuid, profile_uuid("Ocean"));
+        assert_ne!(uuid, profile_uuid("Forest"));
+        let groups: Vec<&str> = uuid.split('-').collect();
+        assert_eq!(
+            groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+            [8, 4, 4, 4, 12]
+        );
+        assert!(groups[2].starts_with('4'));
+        assert!(matches!(groups[3].as_bytes()[0], b'8' | b'9' | b'a' | b'b'));
+        // Quotes in the name can't break out of the shell word
+        assert_eq!(shell_gvariant_string("it's"), r#"''\''it\'\''s'\'''"#);
+    }
+
+    #[test]
+    fn branding_kit_text_passes_aa_on_both_backgrounds() {
+        for primary in crate::palette::web_safe_palette() {
+            let kit = generate_branding_kit(&primary);
+            let light = kit.text_on_light.contrast_ratio(&kit.background_light);
+            let dark = kit.text_on_dark.contrast_ratio(&kit.background_dark);
+            assert!(light >= a11y::AA_NORMAL, "{primary:?} on light: {light}");
+            assert!(dark >= a11y::AA_NORMAL, "{primary:?} on dark: {dark}");
+        }
+    }
+
+    #[test]
+    fn branding_kit_css_lists_every_color() {
+        let kit = generate_branding_kit(&Color::from_rgb(0x33, 0x66, 0x99));
+        let css = kit.to_css_vars();
+        assert!(css.starts_with(":root {\n") && css.ends_with("}\n"));
+        // 3 hues, 4 accents, 10 scale steps, 2 backgrounds and 2 text colors
+        assert_eq!(css.matches("  --brand-").count(), 21);
+        assert!(css.contains("  --brand-primary: #336699;\n"));
+        assert!(css.contains(&format!(
+            "  --brand-text-on-dark: {};\n",
+            kit.text_on_dark.to_hex()
+        )));
+    }
+
+    #[test]
+    fn alacritty_colors_are_a_toml_table_of_lowercase_hex() {
+        let scheme = TerminalScheme::from_base(&Color::from_rgb(40, 120, 200), true);
+        let output =
+            generate_alacritty_colors(&scheme.palette, &scheme.foreground, &scheme.background);
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("[colors]"));
+        // Every other line is `dotted.key = "string"`, the only TOML the
+        // table uses, with no key repeated
+        let mut entries: Vec<(&str, &str)> = Vec::new();
+        for line in lines {
+            let (key, value) = line.split_once(" = ").unwrap_or_else(|| panic!("{line}"));
+            assert!(
+                key.split('.')
+                    .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_lowercase())),
+                "{line}"
+            );
+            let hex = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or_else(|| panic!("{line}"));
+            assert!(hex.len() == 7 && hex.starts_with('#'), "{line}");
+            assert!(
+                hex[1..]
+                    .bytes()
+                    .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+                "{line}"
+            );
+            assert!(!entries.iter().any(|(k, _)| *k == key), "duplicate {key}");
+            entries.push((key, hex));
+        }
+
+        let value = |key: &str| entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        assert_eq!(entries.len(), 18);
+        assert_eq!(
+            value("primary.background"),
+            Some(scheme.background.to_hex().to_ascii_lowercase().as_str())
+        );
+        assert_eq!(
+            value("primary.foreground"),
+            Some(scheme.foreground.to_hex().to_ascii_lowercase().as_str())
+        );
+        for (i, name) in ANSI_NAMES.iter().enumerate() {
+            for (group, color) in [
+                ("normal", &scheme.palette[i]),
+                ("bright", &scheme.palette[i + 8]),
+            ] {
+                assert_eq!(
+                    value(&format!("{group}.{name}")),
+                    Some(color.to_hex().to_ascii_lowercase().as_str()),
+                    "{group}.{name}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Just enough XML to read and write the palette formats we support.
+// Declarations, comments and doctypes are skipped; namespaces are kept as
+// part of the element name.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    Open {
+        name: &'a str,
+        attrs: Vec<(&'a str, String)>,
+        self_closing: bool,
+    },
+    Close(&'a str),
+    Text(String),
+}
+
+impl<'a> Token<'a> {
+    // Value of an attribute on an opening tag
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        match self {
+            Token::Open { attrs, .. } => attrs
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+// Split a document into tags and non-blank text runs
+pub fn tokenize(xml: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = xml;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or("unterminated comment")?;
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("unterminated CDATA section")?;
+            tokens.push(Token::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            let end = rest.find('>').ok_or("unterminated declaration")?;
+            rest = &rest[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or("unterminated closing tag")?;
+            tokens.push(Token::Close(after[..end].trim()));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let (token, consumed) = parse_open_tag(after)?;
+            tokens.push(token);
+            rest = &after[consumed..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if !text.trim().is_empty() {
+                tokens.push(Token::Text(unescape(text.trim())?));
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Parse the inside of `<name attr="value" ...>`, returning the token and the
+// number of bytes consumed including the closing `>`
+fn parse_open_tag(input: &str) -> Result<(Token<'_>, usize), String> {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .ok_or("unterminated tag")?;
+    let name = &input[..name_end];
+    if name.is_empty() {
+        return Err("tag without a name".to_string());
+    }
+
+    let mut attrs = Vec::new();
+    let mut rest = &input[name_end..];
+    loop {
+        rest = rest.trim_start();
+
+        let self_closing = rest.starts_with("/>");
+        if self_closing || rest.starts_with('>') {
+            let after = &rest[if self_closing { 2 } else { 1 }..];
+            let token = Token::Open {
+                name,
+                attrs,
+                self_closing,
+            };
+            return Ok((token, input.len() - after.len()));
+        }
+
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| format!("attribute without a value in <{name}>"))?;
+        let key = rest[..eq].trim();
+        let value_part = rest[eq + 1..].trim_start();
+        let quote = value_part
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("unquoted attribute `{key}` in <{name}>"))?;
+        let value_end = value_part[1..]
+            .find(quote)
+            .ok_or_else(|| format!("unterminated attribute `{key}` in <{name}>"))?;
+        attrs.push((key, unescape(&value_part[1..1 + value_end])?));
+        rest = &value_part[1 + value_end + 1..];
+    }
+}
+
+// Escape text for use in element content or a double-quoted attribute
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let semi = after
+            .find(';')
+            .ok_or_else(|| format!("unterminated entity in `{text}`"))?;
+        let entity = &after[..semi];
+        let decoded = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity `&{entity};`"))?
+            }
+        };
+        out.push(decoded);
+        rest = &after[semi + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}